@@ -0,0 +1,198 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mapping of the dprint configuration onto [`FormatOptions`].
+
+use dprint_core::configuration::{
+    ConfigKeyMap, ConfigKeyValue, ConfigurationDiagnostic, GlobalConfiguration, NewLineKind,
+    ResolveConfigurationResult, get_nullable_value, get_unknown_property_diagnostics, get_value,
+};
+use prttl::options::{EndOfLine, FormatOptions, SpecialPredicateOrder, SpecialSubjectTypeOrder};
+use serde::{Deserialize, Serialize};
+
+/// The default number of spaces per level of indentation,
+/// the same as for the CLI.
+const DEFAULT_INDENT_WIDTH: u8 = 2;
+
+/// The resolved configuration of the plugin.
+///
+/// The defaults are the same as those of the CLI.
+/// See the corresponding fields of [`FormatOptions`] for documentation.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Configuration {
+    pub indent_width: u8,
+    pub use_tabs: bool,
+    pub new_line_kind: NewLineKind,
+    pub single_leafed_new_lines: bool,
+    pub force: bool,
+    pub generate_sorting_ids: bool,
+    pub prioritize_input_order: bool,
+    pub prtr_sorting: bool,
+    pub sparql_syntax: bool,
+    pub max_nesting: bool,
+    pub canonicalize: bool,
+    pub subject_type_order_preset: Option<SpecialSubjectTypeOrder>,
+    pub subject_type_order: Option<Vec<String>>,
+    pub predicate_order_preset: Option<SpecialPredicateOrder>,
+    pub predicate_order: Option<Vec<String>>,
+}
+
+impl From<&Configuration> for FormatOptions {
+    fn from(config: &Configuration) -> Self {
+        let indentation = if config.use_tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(config.indent_width.into())
+        };
+        let end_of_line = match config.new_line_kind {
+            NewLineKind::Auto => EndOfLine::Auto,
+            NewLineKind::LineFeed => EndOfLine::Lf,
+            NewLineKind::CarriageReturnLineFeed => EndOfLine::Crlf,
+        };
+        Self {
+            indentation,
+            single_leafed_new_lines: config.single_leafed_new_lines,
+            force: config.force,
+            generate_sorting_ids: config.generate_sorting_ids,
+            prioritize_input_order: config.prioritize_input_order,
+            prtr_sorting: config.prtr_sorting,
+            sparql_syntax: config.sparql_syntax,
+            max_nesting: config.max_nesting,
+            canonicalize: config.canonicalize,
+            subject_type_order_preset: config.subject_type_order_preset,
+            subject_type_order: config.subject_type_order.clone(),
+            predicate_order_preset: config.predicate_order_preset,
+            predicate_order: config.predicate_order.clone(),
+            end_of_line,
+            ..Self::embedded()
+        }
+    }
+}
+
+/// Gets a list of strings, like a list of prefixed names,
+/// from the configuration.
+fn get_string_list(
+    config: &mut ConfigKeyMap,
+    key: &str,
+    diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) -> Option<Vec<String>> {
+    let strings: Option<Vec<String>> = match config.shift_remove(key)? {
+        ConfigKeyValue::Null => return None,
+        ConfigKeyValue::Array(values) => values
+            .into_iter()
+            .map(|value| {
+                if let ConfigKeyValue::String(string) = value {
+                    Some(string)
+                } else {
+                    None
+                }
+            })
+            .collect(),
+        ConfigKeyValue::String(_)
+        | ConfigKeyValue::Number(_)
+        | ConfigKeyValue::Bool(_)
+        | ConfigKeyValue::Object(_) => None,
+    };
+    if strings.is_none() {
+        diagnostics.push(ConfigurationDiagnostic {
+            property_name: key.to_string(),
+            message: "Expected a list of strings".to_string(),
+        });
+    }
+    strings
+}
+
+/// Resolves the plugin configuration,
+/// falling back to the global dprint configuration
+/// for the indentation and line endings.
+#[must_use]
+pub fn resolve_config(
+    config: ConfigKeyMap,
+    global_config: &GlobalConfiguration,
+) -> ResolveConfigurationResult<Configuration> {
+    let mut config = config;
+    let mut diagnostics = Vec::new();
+    let defaults = FormatOptions::embedded();
+    let resolved_config = Configuration {
+        indent_width: get_value(
+            &mut config,
+            "indentWidth",
+            global_config.indent_width.unwrap_or(DEFAULT_INDENT_WIDTH),
+            &mut diagnostics,
+        ),
+        use_tabs: get_value(
+            &mut config,
+            "useTabs",
+            global_config.use_tabs.unwrap_or(false),
+            &mut diagnostics,
+        ),
+        new_line_kind: get_value(
+            &mut config,
+            "newLineKind",
+            global_config.new_line_kind.unwrap_or(NewLineKind::Auto),
+            &mut diagnostics,
+        ),
+        single_leafed_new_lines: get_value(
+            &mut config,
+            "singleLeafedNewLines",
+            defaults.single_leafed_new_lines,
+            &mut diagnostics,
+        ),
+        force: get_value(&mut config, "force", defaults.force, &mut diagnostics),
+        generate_sorting_ids: get_value(
+            &mut config,
+            "generateSortingIds",
+            defaults.generate_sorting_ids,
+            &mut diagnostics,
+        ),
+        prioritize_input_order: get_value(
+            &mut config,
+            "prioritizeInputOrder",
+            defaults.prioritize_input_order,
+            &mut diagnostics,
+        ),
+        prtr_sorting: get_value(
+            &mut config,
+            "prtrSorting",
+            defaults.prtr_sorting,
+            &mut diagnostics,
+        ),
+        sparql_syntax: get_value(
+            &mut config,
+            "sparqlSyntax",
+            defaults.sparql_syntax,
+            &mut diagnostics,
+        ),
+        max_nesting: get_value(
+            &mut config,
+            "maxNesting",
+            defaults.max_nesting,
+            &mut diagnostics,
+        ),
+        canonicalize: get_value(
+            &mut config,
+            "canonicalize",
+            defaults.canonicalize,
+            &mut diagnostics,
+        ),
+        subject_type_order_preset: get_nullable_value(
+            &mut config,
+            "subjectTypeOrderPreset",
+            &mut diagnostics,
+        ),
+        subject_type_order: get_string_list(&mut config, "subjectTypeOrder", &mut diagnostics),
+        predicate_order_preset: get_nullable_value(
+            &mut config,
+            "predicateOrderPreset",
+            &mut diagnostics,
+        ),
+        predicate_order: get_string_list(&mut config, "predicateOrder", &mut diagnostics),
+    };
+    diagnostics.extend(get_unknown_property_diagnostics(config));
+    ResolveConfigurationResult {
+        config: resolved_config,
+        diagnostics,
+    }
+}
@@ -0,0 +1,46 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! A [dprint](https://dprint.dev) plugin,
+//! formatting Turtle and TriG files with [`prttl`].
+//!
+//! The plugin its self is only built with the `wasm` feature,
+//! for the `wasm32-unknown-unknown` target.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use prttl::options::FormatOptions;
+use prttl::parser::InputFormat;
+
+pub mod configuration;
+#[cfg(feature = "wasm")]
+mod wasm_plugin;
+
+use configuration::Configuration;
+
+/// Formats the content of a Turtle or TriG file,
+/// returning `None` if it is formatted already,
+/// or if formatting is turned off for it with [`prttl::SKIP_MARKER`].
+///
+/// # Errors
+///
+/// Any error from [`prttl::format_str_styled`],
+/// like when the content is not valid Turtle,
+/// or contains comments while [`Configuration::force`] is not set.
+pub fn format_text(
+    file_path: &Path,
+    text: &str,
+    config: &Configuration,
+) -> Result<Option<String>, prttl::error::Error> {
+    if prttl::is_skipped(text) {
+        return Ok(None);
+    }
+    let input_format = InputFormat::from_path(file_path)
+        .filter(|format| *format == InputFormat::TriG)
+        .unwrap_or(InputFormat::Turtle);
+    let options = Arc::new(FormatOptions::from(config));
+    let formatted = prttl::format_str_styled(text, input_format, &options)?;
+    Ok((formatted != text).then_some(formatted))
+}
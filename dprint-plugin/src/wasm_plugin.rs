@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dprint_core::configuration::{ConfigKeyMap, GlobalConfiguration};
+use dprint_core::generate_plugin_code;
+use dprint_core::plugins::{
+    CheckConfigUpdatesMessage, ConfigChange, FileMatchingInfo, FormatResult, PluginInfo,
+    PluginResolveConfigurationResult, SyncFormatRequest, SyncHostFormatRequest, SyncPluginHandler,
+};
+
+use crate::configuration::{Configuration, resolve_config};
+
+/// The key of the plugin section in the dprint configuration.
+const CONFIG_KEY: &str = "turtle";
+
+struct TurtlePluginHandler;
+
+impl SyncPluginHandler<Configuration> for TurtlePluginHandler {
+    fn resolve_config(
+        &mut self,
+        config: ConfigKeyMap,
+        global_config: &GlobalConfiguration,
+    ) -> PluginResolveConfigurationResult<Configuration> {
+        let resolved = resolve_config(config, global_config);
+        PluginResolveConfigurationResult {
+            config: resolved.config,
+            diagnostics: resolved.diagnostics,
+            file_matching: FileMatchingInfo {
+                file_extensions: vec![
+                    prttl::parser::TURTLE_EXTENSION.to_string(),
+                    prttl::parser::TRIG_EXTENSION.to_string(),
+                ],
+                file_names: Vec::new(),
+            },
+        }
+    }
+
+    fn check_config_updates(
+        &self,
+        _message: CheckConfigUpdatesMessage,
+    ) -> anyhow::Result<Vec<ConfigChange>> {
+        Ok(Vec::new())
+    }
+
+    fn plugin_info(&mut self) -> PluginInfo {
+        PluginInfo {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config_key: CONFIG_KEY.to_string(),
+            help_url: env!("CARGO_PKG_REPOSITORY").to_string(),
+            config_schema_url: String::new(),
+            update_url: None,
+        }
+    }
+
+    fn license_text(&mut self) -> String {
+        include_str!("../../LICENSE").to_string()
+    }
+
+    fn format(
+        &mut self,
+        request: SyncFormatRequest<Configuration>,
+        _format_with_host: impl FnMut(SyncHostFormatRequest) -> FormatResult,
+    ) -> FormatResult {
+        // Sorting moves content around, so only whole files can be formatted
+        if request.range.is_some() {
+            return Ok(None);
+        }
+        let text = String::from_utf8(request.file_bytes)?;
+        let formatted = crate::format_text(request.file_path, &text, request.config)?;
+        Ok(formatted.map(String::into_bytes))
+    }
+}
+
+generate_plugin_code!(TurtlePluginHandler, TurtlePluginHandler);
+
+/// Fills `dest` with bytes that differ on each call,
+/// in place of random ones, which dprint does not offer.
+///
+/// They only seed the generator of the IDs of blank nodes,
+/// which need to be unique, but not unpredictable.
+// The signature is the one getrandom expects
+#[allow(clippy::unnecessary_wraps)]
+fn fill_unique(dest: &mut [u8]) -> Result<(), getrandom::Error> {
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let call = CALLS.fetch_add(1, Ordering::Relaxed).to_le_bytes();
+    for (byte, call_byte) in dest.iter_mut().zip(call.iter().cycle()) {
+        *byte = *call_byte;
+    }
+    Ok(())
+}
+
+getrandom::register_custom_getrandom!(fill_unique);
@@ -0,0 +1,52 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+
+use dprint_core::configuration::{ConfigKeyMap, GlobalConfiguration};
+use dprint_plugin_prttl::configuration::{Configuration, resolve_config};
+use dprint_plugin_prttl::format_text;
+
+fn config() -> Configuration {
+    resolve_config(ConfigKeyMap::new(), &GlobalConfiguration::default()).config
+}
+
+#[test]
+fn formats() {
+    let formatted = format_text(
+        Path::new("a.ttl"),
+        "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n",
+        &config(),
+    )
+    .unwrap();
+    assert_eq!(
+        formatted.as_deref(),
+        Some("PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n")
+    );
+}
+
+#[test]
+fn leaves_formatted_text() {
+    let text = "PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n";
+    assert_eq!(
+        format_text(Path::new("a.ttl"), text, &config()).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn leaves_skipped_text() {
+    let text = "# prttl: skip - generated\n@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n";
+    assert_eq!(
+        format_text(Path::new("a.ttl"), text, &config()).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn formats_trig_by_extension() {
+    let text = "<http://example.org/g> { <http://example.org/s> <http://example.org/p> 1 . }\n";
+    assert!(format_text(Path::new("a.trig"), text, &config()).is_ok());
+    assert!(format_text(Path::new("a.ttl"), text, &config()).is_err());
+}
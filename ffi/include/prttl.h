@@ -0,0 +1,316 @@
+/*
+ * SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#ifndef PRTTL_H
+#define PRTTL_H
+
+/* Generated with cbindgen from ffi/src; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The result of a call.
+//
+// Any value other than `PRTTL_STATUS_OK` means the call failed;
+// use `prttl_last_error_message` and its siblings for the details.
+typedef enum PrttlStatus {
+  PRTTL_STATUS_OK = 0,
+  // A required pointer argument was null.
+  PRTTL_STATUS_NULL_POINTER = 1,
+  // An argument had an invalid value, like an unknown preset name.
+  PRTTL_STATUS_INVALID_ARGUMENT = 2,
+  // The input or a string argument is not valid UTF-8.
+  PRTTL_STATUS_INVALID_UTF8 = 3,
+  // prttl panicked, which is a bug.
+  PRTTL_STATUS_PANIC = 4,
+  PRTTL_STATUS_CANONICALIZATION = 10,
+  PRTTL_STATUS_PREFIX_REDEFINITION = 11,
+  PRTTL_STATUS_MULTIPLE_PREFIXES_FOR_NAMESPACE = 12,
+  PRTTL_STATUS_COMMENT = 13,
+  PRTTL_STATUS_BASE_REDEFINITION = 14,
+  PRTTL_STATUS_PREFIX_AND_BASE_SHARE_NAMESPACE = 15,
+  PRTTL_STATUS_TURTLE_SYNTAX = 16,
+  PRTTL_STATUS_BASE_IRI = 17,
+  PRTTL_STATUS_RDF_XML_SYNTAX = 18,
+  PRTTL_STATUS_JSON_LD_SYNTAX = 19,
+  PRTTL_STATUS_NAMED_GRAPH = 20,
+  PRTTL_STATUS_CHECK = 30,
+  PRTTL_STATUS_MULTIPLE_BASES = 31,
+  PRTTL_STATUS_FORMAT = 32,
+  PRTTL_STATUS_SORTING_ID_GENERATION = 33,
+  PRTTL_STATUS_FAILED_TO_CREATE_TURTLE_STRUCTURE = 34,
+  PRTTL_STATUS_CONFIG = 35,
+  PRTTL_STATUS_UNKNOWN_INPUT_FORMAT = 36,
+  PRTTL_STATUS_TARGET_FILE_DOES_NOT_EXIST = 37,
+  PRTTL_STATUS_FAILED_TO_READ_TARGET_FILE = 38,
+  PRTTL_STATUS_FAILED_TO_WRITE_FORMATTED_FILE = 39,
+  PRTTL_STATUS_FAILED_TO_READ_STDIN = 40,
+  PRTTL_STATUS_FAILED_TO_WRITE_STDOUT = 41,
+  PRTTL_STATUS_OUTPUT_FILE_EXISTS = 42,
+  PRTTL_STATUS_OUTPUT_FOR_MULTIPLE_INPUTS = 43,
+  PRTTL_STATUS_FAILED_TO_CREATE_OUTPUT_DIR = 44,
+  PRTTL_STATUS_FAILED_TO_RUN_GIT = 45,
+  PRTTL_STATUS_GIT_COMMAND_FAILED = 46,
+  PRTTL_STATUS_INVALID_LSP_MESSAGE = 47,
+  PRTTL_STATUS_FAILED_TO_LISTEN = 48,
+  PRTTL_STATUS_FAILED_TO_WALK_INPUT_DIR = 49,
+  PRTTL_STATUS_INVALID_GLOB = 50,
+  PRTTL_STATUS_FAILED_TO_WATCH = 51,
+  PRTTL_STATUS_FAILED_TO_LIST_FILES_IN_INPUT_DIR = 52,
+  // An error not known to this version of the bindings.
+  PRTTL_STATUS_OTHER = 99,
+} PrttlStatus;
+
+// The formatting options, mirroring `FormatOptions` of the Rust library.
+//
+// Created with `prttl_options_new`, which sets the defaults of the CLI,
+// changed with the `prttl_options_set_*` functions,
+// and released with `prttl_options_free`.
+typedef struct PrttlOptions PrttlOptions;
+
+// A range within the input.
+// Lines and columns are 1-based.
+typedef struct PrttlLocation {
+  uint64_t start_line;
+  uint64_t start_column;
+  uint64_t end_line;
+  uint64_t end_column;
+} PrttlLocation;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Formats Turtle.
+//
+// `options` may be null, to use the defaults of the CLI.
+// `input` has to be UTF-8, and is not required to be NUL terminated.
+//
+// On success, `*output` is set to the formatted text,
+// which is NUL terminated,
+// and `*output_len` to its length in bytes, excluding the terminating NUL.
+// The output has to be released with `prttl_buffer_free`.
+// On failure, neither is touched.
+//
+// # Safety
+//
+// `options` has to be null or come from `prttl_options_new`, and not be freed yet.
+// `input` has to point to `input_len` readable bytes.
+// `output` and `output_len` have to point to writable memory.
+enum PrttlStatus prttl_format_turtle(const struct PrttlOptions *options,
+                                     const uint8_t *input,
+                                     size_t input_len,
+                                     uint8_t **output,
+                                     size_t *output_len);
+
+// Formats TriG,
+// the same way as `prttl_format_turtle` does Turtle.
+//
+// # Safety
+//
+// See `prttl_format_turtle`.
+enum PrttlStatus prttl_format_trig(const struct PrttlOptions *options,
+                                   const uint8_t *input,
+                                   size_t input_len,
+                                   uint8_t **output,
+                                   size_t *output_len);
+
+// Releases the output of `prttl_format_turtle` or `prttl_format_trig`.
+// Does nothing if `buffer` is null.
+//
+// # Safety
+//
+// `buffer` has to be null or an output of the formatting functions,
+// with `len` being the length returned with it,
+// and not be freed yet.
+void prttl_buffer_free(uint8_t *buffer, size_t len);
+
+// The version of prttl, as a NUL terminated string owned by prttl,
+// or null if that fails, which never happens, short of bugs.
+const char *prttl_version(void);
+
+// The message of the error of the last failed call on this thread,
+// or null if the last call succeeded.
+//
+// The string is owned by prttl,
+// and stays valid until the next call on this thread that returns a `PrttlStatus`.
+const char *prttl_last_error_message(void);
+
+// A short, stable, machine-readable name of the error of the last failed call on this thread,
+// like "comment" or "turtle-syntax",
+// or null if the last call succeeded.
+//
+// The string is owned by prttl,
+// and stays valid until the next call on this thread that returns a `PrttlStatus`.
+const char *prttl_last_error_kind(void);
+
+// Writes where in the input the error of the last failed call on this thread occurred
+// to `location`, if known.
+//
+// Returns whether it was known.
+//
+// # Safety
+//
+// `location` has to be null or point to a writable `PrttlLocation`.
+bool prttl_last_error_location(struct PrttlLocation *location);
+
+// Creates options with the defaults of the CLI.
+//
+// Returns null if that fails, which never happens, short of bugs.
+// The options have to be released with `prttl_options_free`.
+struct PrttlOptions *prttl_options_new(void);
+
+// Releases options created with `prttl_options_new`.
+// Does nothing if `options` is null.
+//
+// # Safety
+//
+// `options` has to be null or come from `prttl_options_new`, and not be freed yet.
+void prttl_options_free(struct PrttlOptions *options);
+
+// Sets the space(s) or tab(s) representing one level of indentation;
+// two spaces by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+// `indentation` has to point to a NUL terminated string.
+enum PrttlStatus prttl_options_set_indentation(struct PrttlOptions *options,
+                                               const char *indentation);
+
+// Sets whether to move a single/lone predicate-object pair
+// or object alone onto a new line;
+// false by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+enum PrttlStatus prttl_options_set_single_leafed_new_lines(struct PrttlOptions *options,
+                                                           bool value);
+
+// Sets whether to format input with comments, which get removed;
+// false by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+enum PrttlStatus prttl_options_set_force(struct PrttlOptions *options, bool value);
+
+// Sets whether to generate `prtr:sortingId`s for blank nodes;
+// false by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+enum PrttlStatus prttl_options_set_generate_sorting_ids(struct PrttlOptions *options, bool value);
+
+// Sets whether generating `prtr:sortingId`s rather keeps the order of the input
+// than the IDs already assigned;
+// false by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+enum PrttlStatus prttl_options_set_prioritize_input_order(struct PrttlOptions *options, bool value);
+
+// Sets whether to sort blank nodes by their `prtr:sortingId`, if any;
+// true by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+enum PrttlStatus prttl_options_set_prtr_sorting(struct PrttlOptions *options, bool value);
+
+// Sets whether to use SPARQL-ish syntax for base and prefix,
+// or the traditional Turtle syntax;
+// true by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+enum PrttlStatus prttl_options_set_sparql_syntax(struct PrttlOptions *options, bool value);
+
+// Sets whether to nest blank nodes where possible,
+// or rather label all of them;
+// true by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+enum PrttlStatus prttl_options_set_max_nesting(struct PrttlOptions *options, bool value);
+
+// Sets whether to canonicalize the input before formatting;
+// false by default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+enum PrttlStatus prttl_options_set_canonicalize(struct PrttlOptions *options, bool value);
+
+// Sets a predefined order of subject types to be used for sorting;
+// one of "owl", "skos", "shacl", "shex" and "rdf",
+// or null for none, which is the default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+// `preset` has to be null or point to a NUL terminated string.
+enum PrttlStatus prttl_options_set_subject_type_order_preset(struct PrttlOptions *options,
+                                                             const char *preset);
+
+// Sets a custom order of subject types to be used for sorting,
+// as `len` IRIs or prefixed names,
+// or null for none, which is the default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+// `types` has to be null or point to `len` pointers to NUL terminated strings.
+enum PrttlStatus prttl_options_set_subject_type_order(struct PrttlOptions *options,
+                                                      const char *const *types,
+                                                      size_t len);
+
+// Sets a predefined order of predicates to be used for sorting;
+// one of "owl", "skos", "shacl", "shex" and "rdf",
+// or null for none, which is the default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+// `preset` has to be null or point to a NUL terminated string.
+enum PrttlStatus prttl_options_set_predicate_order_preset(struct PrttlOptions *options,
+                                                          const char *preset);
+
+// Sets a custom order of predicates to be used for sorting,
+// as `len` IRIs or prefixed names,
+// or null for none, which is the default.
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+// `predicates` has to be null or point to `len` pointers to NUL terminated strings.
+enum PrttlStatus prttl_options_set_predicate_order(struct PrttlOptions *options,
+                                                   const char *const *predicates,
+                                                   size_t len);
+
+// Sets the line endings of the output;
+// one of "auto" (those of the input, the default), "lf" and "crlf".
+//
+// # Safety
+//
+// `options` has to come from `prttl_options_new`, and not be freed yet.
+// `end_of_line` has to point to a NUL terminated string.
+enum PrttlStatus prttl_options_set_end_of_line(struct PrttlOptions *options,
+                                               const char *end_of_line);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PRTTL_H */
@@ -0,0 +1,311 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use prttl::error::Error;
+use prttl::parser;
+
+/// The result of a call.
+///
+/// Any value other than `PRTTL_STATUS_OK` means the call failed;
+/// use `prttl_last_error_message` and its siblings for the details.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrttlStatus {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// An argument had an invalid value, like an unknown preset name.
+    InvalidArgument = 2,
+    /// The input or a string argument is not valid UTF-8.
+    InvalidUtf8 = 3,
+    /// prttl panicked, which is a bug.
+    Panic = 4,
+
+    // The kinds of `prttl::parser::Error`
+    Canonicalization = 10,
+    PrefixRedefinition = 11,
+    MultiplePrefixesForNamespace = 12,
+    Comment = 13,
+    BaseRedefinition = 14,
+    PrefixAndBaseShareNamespace = 15,
+    TurtleSyntax = 16,
+    BaseIri = 17,
+    RdfXmlSyntax = 18,
+    JsonLdSyntax = 19,
+    NamedGraph = 20,
+
+    // The other kinds of `prttl::error::Error`
+    Check = 30,
+    MultipleBases = 31,
+    Format = 32,
+    SortingIdGeneration = 33,
+    FailedToCreateTurtleStructure = 34,
+    Config = 35,
+    UnknownInputFormat = 36,
+    TargetFileDoesNotExist = 37,
+    FailedToReadTargetFile = 38,
+    FailedToWriteFormattedFile = 39,
+    FailedToReadStdin = 40,
+    FailedToWriteStdout = 41,
+    OutputFileExists = 42,
+    OutputForMultipleInputs = 43,
+    FailedToCreateOutputDir = 44,
+    FailedToRunGit = 45,
+    GitCommandFailed = 46,
+    InvalidLspMessage = 47,
+    FailedToListen = 48,
+    FailedToWalkInputDir = 49,
+    InvalidGlob = 50,
+    FailedToWatch = 51,
+    FailedToListFilesInInputDir = 52,
+    /// An error not known to this version of the bindings.
+    Other = 99,
+}
+
+impl From<&parser::Error> for PrttlStatus {
+    fn from(err: &parser::Error) -> Self {
+        match err {
+            parser::Error::Canonicalization(_) => Self::Canonicalization,
+            parser::Error::PrefixRedefinition(_) => Self::PrefixRedefinition,
+            parser::Error::MultiplePrefixesForNamespace(_) => Self::MultiplePrefixesForNamespace,
+            parser::Error::Comment => Self::Comment,
+            parser::Error::BaseRedefinition => Self::BaseRedefinition,
+            parser::Error::PrefixAndBaseShareNamespace(_) => Self::PrefixAndBaseShareNamespace,
+            parser::Error::TurtleSyntaxError(_) => Self::TurtleSyntax,
+            parser::Error::BaseIri(_) => Self::BaseIri,
+            parser::Error::RdfXmlSyntaxError(_) => Self::RdfXmlSyntax,
+            parser::Error::JsonLdSyntaxError(_) => Self::JsonLdSyntax,
+            parser::Error::NamedGraph => Self::NamedGraph,
+        }
+    }
+}
+
+impl From<&Error> for PrttlStatus {
+    fn from(err: &Error) -> Self {
+        match err {
+            Error::File(_, inner) => Self::from(inner.as_ref()),
+            Error::ParseError(parse_err) => Self::from(parse_err),
+            Error::TurtleSyntaxError(_) => Self::TurtleSyntax,
+            Error::InvalidUtf8(_, _) => Self::InvalidUtf8,
+            Error::Check(_) => Self::Check,
+            Error::MultipleBases => Self::MultipleBases,
+            Error::Format(_) => Self::Format,
+            Error::SortingIdGeneration(_) => Self::SortingIdGeneration,
+            Error::FailedToCreateTurtleStructure(_) => Self::FailedToCreateTurtleStructure,
+            Error::Config(_) => Self::Config,
+            Error::UnknownInputFormat(_) => Self::UnknownInputFormat,
+            Error::TargetFileDoesNotExist(_) => Self::TargetFileDoesNotExist,
+            Error::FailedToReadTargetFile(_, _) => Self::FailedToReadTargetFile,
+            Error::FailedToWriteFormattedFile(_, _, _) => Self::FailedToWriteFormattedFile,
+            Error::FailedToListFilesInInputDir(_, _, _) => Self::FailedToListFilesInInputDir,
+            Error::FailedToReadStdin(_) => Self::FailedToReadStdin,
+            Error::FailedToWriteStdout(_) => Self::FailedToWriteStdout,
+            Error::OutputFileExists(_) => Self::OutputFileExists,
+            Error::OutputForMultipleInputs(_) => Self::OutputForMultipleInputs,
+            Error::FailedToCreateOutputDir(_, _) => Self::FailedToCreateOutputDir,
+            Error::FailedToRunGit(_, _) => Self::FailedToRunGit,
+            Error::GitCommandFailed(_, _) => Self::GitCommandFailed,
+            Error::InvalidLspMessage(_) => Self::InvalidLspMessage,
+            Error::FailedToListen(_, _) => Self::FailedToListen,
+            Error::FailedToWalkInputDir(_, _) => Self::FailedToWalkInputDir,
+            Error::InvalidGlob(_) => Self::InvalidGlob,
+            Error::FailedToWatch(_) => Self::FailedToWatch,
+            // `Error` is non-exhaustive
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A range within the input.
+/// Lines and columns are 1-based.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct PrttlLocation {
+    pub start_line: u64,
+    pub start_column: u64,
+    pub end_line: u64,
+    pub end_column: u64,
+}
+
+/// Why a call failed.
+pub(crate) struct Failure {
+    status: PrttlStatus,
+    kind: &'static str,
+    message: String,
+    location: Option<PrttlLocation>,
+}
+
+impl Failure {
+    pub(crate) fn new(status: PrttlStatus, kind: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            kind,
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    pub(crate) fn null_pointer(argument: &str) -> Self {
+        Self::new(
+            PrttlStatus::NullPointer,
+            "null-pointer",
+            format!("'{argument}' must not be null"),
+        )
+    }
+
+    pub(crate) fn invalid_argument(argument: &str, reason: impl std::fmt::Display) -> Self {
+        Self::new(
+            PrttlStatus::InvalidArgument,
+            "invalid-argument",
+            format!("Invalid value for '{argument}': {reason}"),
+        )
+    }
+
+    pub(crate) fn invalid_utf8(argument: &str, offset: usize) -> Self {
+        Self::new(
+            PrttlStatus::InvalidUtf8,
+            "invalid-utf8",
+            format!("'{argument}' is not valid UTF-8, starting at byte offset {offset}"),
+        )
+    }
+
+    fn panic(payload: &(dyn Any + Send)) -> Self {
+        let reason = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown cause");
+        Self::new(
+            PrttlStatus::Panic,
+            "panic",
+            format!("prttl panicked, which is a bug: {reason}"),
+        )
+    }
+}
+
+impl From<&Error> for Failure {
+    fn from(err: &Error) -> Self {
+        Self {
+            status: PrttlStatus::from(err),
+            kind: err.kind(),
+            message: err.to_string(),
+            location: err.location_1based().map(|range| PrttlLocation {
+                start_line: range.start.line,
+                start_column: range.start.column,
+                end_line: range.end.line,
+                end_column: range.end.column,
+            }),
+        }
+    }
+}
+
+/// The details of the last failed call, as handed out to C.
+struct LastError {
+    kind: CString,
+    message: CString,
+    location: Option<PrttlLocation>,
+}
+
+impl From<Failure> for LastError {
+    fn from(failure: Failure) -> Self {
+        Self {
+            kind: to_c_string(failure.kind.to_string()),
+            message: to_c_string(failure.message),
+            location: failure.location,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<LastError>> = const { RefCell::new(None) };
+}
+
+/// Converts to a C string,
+/// escaping NUL characters, which C strings can not contain.
+pub(crate) fn to_c_string(text: String) -> CString {
+    CString::new(text).unwrap_or_else(|err| {
+        let escaped = String::from_utf8_lossy(&err.into_vec()).replace('\0', "\\0");
+        CString::new(escaped).unwrap_or_default()
+    })
+}
+
+/// Runs `call`, records how it failed, if it did,
+/// and keeps panics from unwinding into C.
+pub(crate) fn guard(call: impl FnOnce() -> Result<(), Failure>) -> PrttlStatus {
+    let result = panic::catch_unwind(AssertUnwindSafe(call))
+        .unwrap_or_else(|payload| Err(Failure::panic(payload.as_ref())));
+    let (status, last_error) = match result {
+        Ok(()) => (PrttlStatus::Ok, None),
+        Err(failure) => (failure.status, Some(LastError::from(failure))),
+    };
+    LAST_ERROR.with(|cell| cell.replace(last_error));
+    status
+}
+
+/// Runs `call`, returning `fallback` if it panics.
+/// For calls that do not return a status.
+pub(crate) fn guard_or<T>(fallback: T, call: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(call)).unwrap_or(fallback)
+}
+
+fn last_error_str(select: fn(&LastError) -> &CString) -> *const c_char {
+    guard_or(ptr::null(), || {
+        LAST_ERROR.with(|cell| {
+            cell.borrow()
+                .as_ref()
+                .map_or(ptr::null(), |last| select(last).as_ptr())
+        })
+    })
+}
+
+/// The message of the error of the last failed call on this thread,
+/// or null if the last call succeeded.
+///
+/// The string is owned by prttl,
+/// and stays valid until the next call on this thread that returns a `PrttlStatus`.
+#[unsafe(no_mangle)]
+pub extern "C" fn prttl_last_error_message() -> *const c_char {
+    last_error_str(|last| &last.message)
+}
+
+/// A short, stable, machine-readable name of the error of the last failed call on this thread,
+/// like "comment" or "turtle-syntax",
+/// or null if the last call succeeded.
+///
+/// The string is owned by prttl,
+/// and stays valid until the next call on this thread that returns a `PrttlStatus`.
+#[unsafe(no_mangle)]
+pub extern "C" fn prttl_last_error_kind() -> *const c_char {
+    last_error_str(|last| &last.kind)
+}
+
+/// Writes where in the input the error of the last failed call on this thread occurred
+/// to `location`, if known.
+///
+/// Returns whether it was known.
+///
+/// # Safety
+///
+/// `location` has to be null or point to a writable `PrttlLocation`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_last_error_location(location: *mut PrttlLocation) -> bool {
+    guard_or(false, || {
+        let known = LAST_ERROR.with(|cell| cell.borrow().as_ref().and_then(|last| last.location));
+        let Some(known) = known else {
+            return false;
+        };
+        if location.is_null() {
+            return false;
+        }
+        // SAFETY: The caller guarantees that non-null pointers are valid
+        unsafe { location.write(known) };
+        true
+    })
+}
@@ -0,0 +1,163 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! C bindings for [`prttl`],
+//! built as a shared and a static library,
+//! with the header `include/prttl.h` generated by [cbindgen](https://github.com/mozilla/cbindgen).
+//!
+//! All functions returning a [`error::PrttlStatus`]
+//! record the details of a failure for the calling thread,
+//! to be retrieved with [`error::prttl_last_error_message`] and its siblings.
+//! Panics never unwind into the calling code;
+//! they are reported as [`error::PrttlStatus::Panic`] instead.
+
+pub mod error;
+pub mod options;
+
+use std::ffi::{CString, c_char};
+use std::ptr;
+use std::sync::{Arc, LazyLock};
+
+use prttl::options::FormatOptions;
+use prttl::parser::InputFormat;
+
+use crate::error::{Failure, PrttlStatus, guard, guard_or, to_c_string};
+use crate::options::PrttlOptions;
+
+/// Formats the UTF-8 `input` of `input_len` bytes.
+///
+/// # Safety
+///
+/// See [`prttl_format_turtle`].
+unsafe fn format(
+    input_format: InputFormat,
+    options: *const PrttlOptions,
+    input: *const u8,
+    input_len: usize,
+    output: *mut *mut u8,
+    output_len: *mut usize,
+) -> PrttlStatus {
+    guard(|| {
+        if input.is_null() {
+            return Err(Failure::null_pointer("input"));
+        }
+        if output.is_null() {
+            return Err(Failure::null_pointer("output"));
+        }
+        if output_len.is_null() {
+            return Err(Failure::null_pointer("output_len"));
+        }
+        // SAFETY: The caller guarantees that non-null pointers are valid
+        let options = unsafe { options.as_ref() }
+            .map_or_else(FormatOptions::embedded, |options| options.0.clone());
+        // SAFETY: The caller guarantees that `input` points to `input_len` bytes
+        let input = unsafe { std::slice::from_raw_parts(input, input_len) };
+        let input = std::str::from_utf8(input)
+            .map_err(|err| Failure::invalid_utf8("input", err.valid_up_to()))?;
+        let formatted = prttl::format_str_styled(input, input_format, &Arc::new(options))
+            .map_err(|err| Failure::from(&err))?;
+        let mut bytes = formatted.into_bytes();
+        let len = bytes.len();
+        bytes.push(0);
+        let bytes = Box::into_raw(bytes.into_boxed_slice());
+        // SAFETY: Checked for null above, and the caller guarantees they are valid otherwise
+        unsafe {
+            output.write(bytes.cast::<u8>());
+            output_len.write(len);
+        }
+        Ok(())
+    })
+}
+
+/// Formats Turtle.
+///
+/// `options` may be null, to use the defaults of the CLI.
+/// `input` has to be UTF-8, and is not required to be NUL terminated.
+///
+/// On success, `*output` is set to the formatted text,
+/// which is NUL terminated,
+/// and `*output_len` to its length in bytes, excluding the terminating NUL.
+/// The output has to be released with `prttl_buffer_free`.
+/// On failure, neither is touched.
+///
+/// # Safety
+///
+/// `options` has to be null or come from `prttl_options_new`, and not be freed yet.
+/// `input` has to point to `input_len` readable bytes.
+/// `output` and `output_len` have to point to writable memory.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_format_turtle(
+    options: *const PrttlOptions,
+    input: *const u8,
+    input_len: usize,
+    output: *mut *mut u8,
+    output_len: *mut usize,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        format(
+            InputFormat::Turtle,
+            options,
+            input,
+            input_len,
+            output,
+            output_len,
+        )
+    }
+}
+
+/// Formats TriG,
+/// the same way as `prttl_format_turtle` does Turtle.
+///
+/// # Safety
+///
+/// See `prttl_format_turtle`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_format_trig(
+    options: *const PrttlOptions,
+    input: *const u8,
+    input_len: usize,
+    output: *mut *mut u8,
+    output_len: *mut usize,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        format(
+            InputFormat::TriG,
+            options,
+            input,
+            input_len,
+            output,
+            output_len,
+        )
+    }
+}
+
+/// Releases the output of `prttl_format_turtle` or `prttl_format_trig`.
+/// Does nothing if `buffer` is null.
+///
+/// # Safety
+///
+/// `buffer` has to be null or an output of the formatting functions,
+/// with `len` being the length returned with it,
+/// and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_buffer_free(buffer: *mut u8, len: usize) {
+    guard_or((), || {
+        if !buffer.is_null() {
+            // Including the terminating NUL
+            let slice = ptr::slice_from_raw_parts_mut(buffer, len + 1);
+            // SAFETY: The caller guarantees that it came from `Box::into_raw`
+            drop(unsafe { Box::from_raw(slice) });
+        }
+    });
+}
+
+/// The version of prttl, as a NUL terminated string owned by prttl,
+/// or null if that fails, which never happens, short of bugs.
+#[unsafe(no_mangle)]
+pub extern "C" fn prttl_version() -> *const c_char {
+    static VERSION: LazyLock<CString> = LazyLock::new(|| to_c_string(prttl::VERSION.to_string()));
+    guard_or(ptr::null(), || VERSION.as_ptr())
+}
@@ -0,0 +1,428 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ffi::{CStr, c_char};
+use std::ptr;
+use std::str::FromStr;
+
+use prttl::options::{EndOfLine, FormatOptions, SpecialPredicateOrder, SpecialSubjectTypeOrder};
+
+use crate::error::{Failure, PrttlStatus, guard, guard_or};
+
+/// The formatting options, mirroring `FormatOptions` of the Rust library.
+///
+/// Created with `prttl_options_new`, which sets the defaults of the CLI,
+/// changed with the `prttl_options_set_*` functions,
+/// and released with `prttl_options_free`.
+pub struct PrttlOptions(pub(crate) FormatOptions);
+
+/// Reads a string argument.
+///
+/// # Safety
+///
+/// `text` has to be null or point to a NUL terminated string.
+unsafe fn read_str<'a>(argument: &str, text: *const c_char) -> Result<&'a str, Failure> {
+    if text.is_null() {
+        return Err(Failure::null_pointer(argument));
+    }
+    // SAFETY: The caller guarantees that non-null pointers are valid
+    let text = unsafe { CStr::from_ptr(text) };
+    text.to_str()
+        .map_err(|err| Failure::invalid_utf8(argument, err.valid_up_to()))
+}
+
+/// Reads the name of a preset or another choice,
+/// like `"owl"` for [`SpecialPredicateOrder::Owl`];
+/// null for none.
+///
+/// # Safety
+///
+/// `name` has to be null or point to a NUL terminated string.
+unsafe fn read_choice<T: FromStr>(argument: &str, name: *const c_char) -> Result<Option<T>, Failure>
+where
+    T::Err: std::fmt::Display,
+{
+    if name.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: Passed on from the caller
+    let name = unsafe { read_str(argument, name) }?;
+    name.parse()
+        .map(Some)
+        .map_err(|err| Failure::invalid_argument(argument, err))
+}
+
+/// Reads a list of strings, like IRIs or prefixed names;
+/// null for none.
+///
+/// # Safety
+///
+/// `items` has to be null or point to `len` pointers to NUL terminated strings.
+unsafe fn read_list(
+    argument: &str,
+    items: *const *const c_char,
+    len: usize,
+) -> Result<Option<Vec<String>>, Failure> {
+    if items.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: The caller guarantees that non-null pointers are valid
+    let items = unsafe { std::slice::from_raw_parts(items, len) };
+    items
+        .iter()
+        // SAFETY: Passed on from the caller
+        .map(|item| unsafe { read_str(argument, *item) }.map(str::to_string))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// Changes the options behind `options`.
+///
+/// # Safety
+///
+/// `options` has to be null or come from `prttl_options_new`, and not be freed yet.
+unsafe fn update(
+    options: *mut PrttlOptions,
+    change: impl FnOnce(&mut FormatOptions) -> Result<(), Failure>,
+) -> PrttlStatus {
+    guard(|| {
+        // SAFETY: The caller guarantees that non-null pointers are valid
+        let options =
+            unsafe { options.as_mut() }.ok_or_else(|| Failure::null_pointer("options"))?;
+        change(&mut options.0)
+    })
+}
+
+/// Creates options with the defaults of the CLI.
+///
+/// Returns null if that fails, which never happens, short of bugs.
+/// The options have to be released with `prttl_options_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn prttl_options_new() -> *mut PrttlOptions {
+    guard_or(ptr::null_mut(), || {
+        Box::into_raw(Box::new(PrttlOptions(FormatOptions::embedded())))
+    })
+}
+
+/// Releases options created with `prttl_options_new`.
+/// Does nothing if `options` is null.
+///
+/// # Safety
+///
+/// `options` has to be null or come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_free(options: *mut PrttlOptions) {
+    guard_or((), || {
+        if !options.is_null() {
+            // SAFETY: The caller guarantees that it came from `Box::into_raw`
+            drop(unsafe { Box::from_raw(options) });
+        }
+    });
+}
+
+/// Sets the space(s) or tab(s) representing one level of indentation;
+/// two spaces by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+/// `indentation` has to point to a NUL terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_indentation(
+    options: *mut PrttlOptions,
+    indentation: *const c_char,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            let indentation = read_str("indentation", indentation)?;
+            if !indentation.chars().all(|chr| chr == ' ' || chr == '\t') {
+                return Err(Failure::invalid_argument(
+                    "indentation",
+                    "only spaces and tabs are allowed",
+                ));
+            }
+            options.indentation = indentation.to_string();
+            Ok(())
+        })
+    }
+}
+
+/// Sets whether to move a single/lone predicate-object pair
+/// or object alone onto a new line;
+/// false by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_single_leafed_new_lines(
+    options: *mut PrttlOptions,
+    value: bool,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.single_leafed_new_lines = value;
+            Ok(())
+        })
+    }
+}
+
+/// Sets whether to format input with comments, which get removed;
+/// false by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_force(
+    options: *mut PrttlOptions,
+    value: bool,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.force = value;
+            Ok(())
+        })
+    }
+}
+
+/// Sets whether to generate `prtr:sortingId`s for blank nodes;
+/// false by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_generate_sorting_ids(
+    options: *mut PrttlOptions,
+    value: bool,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.generate_sorting_ids = value;
+            Ok(())
+        })
+    }
+}
+
+/// Sets whether generating `prtr:sortingId`s rather keeps the order of the input
+/// than the IDs already assigned;
+/// false by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_prioritize_input_order(
+    options: *mut PrttlOptions,
+    value: bool,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.prioritize_input_order = value;
+            Ok(())
+        })
+    }
+}
+
+/// Sets whether to sort blank nodes by their `prtr:sortingId`, if any;
+/// true by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_prtr_sorting(
+    options: *mut PrttlOptions,
+    value: bool,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.prtr_sorting = value;
+            Ok(())
+        })
+    }
+}
+
+/// Sets whether to use SPARQL-ish syntax for base and prefix,
+/// or the traditional Turtle syntax;
+/// true by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_sparql_syntax(
+    options: *mut PrttlOptions,
+    value: bool,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.sparql_syntax = value;
+            Ok(())
+        })
+    }
+}
+
+/// Sets whether to nest blank nodes where possible,
+/// or rather label all of them;
+/// true by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_max_nesting(
+    options: *mut PrttlOptions,
+    value: bool,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.max_nesting = value;
+            Ok(())
+        })
+    }
+}
+
+/// Sets whether to canonicalize the input before formatting;
+/// false by default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_canonicalize(
+    options: *mut PrttlOptions,
+    value: bool,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.canonicalize = value;
+            Ok(())
+        })
+    }
+}
+
+/// Sets a predefined order of subject types to be used for sorting;
+/// one of "owl", "skos", "shacl", "shex" and "rdf",
+/// or null for none, which is the default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+/// `preset` has to be null or point to a NUL terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_subject_type_order_preset(
+    options: *mut PrttlOptions,
+    preset: *const c_char,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.subject_type_order_preset =
+                read_choice::<SpecialSubjectTypeOrder>("subject_type_order_preset", preset)?;
+            Ok(())
+        })
+    }
+}
+
+/// Sets a custom order of subject types to be used for sorting,
+/// as `len` IRIs or prefixed names,
+/// or null for none, which is the default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+/// `types` has to be null or point to `len` pointers to NUL terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_subject_type_order(
+    options: *mut PrttlOptions,
+    types: *const *const c_char,
+    len: usize,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.subject_type_order = read_list("subject_type_order", types, len)?;
+            Ok(())
+        })
+    }
+}
+
+/// Sets a predefined order of predicates to be used for sorting;
+/// one of "owl", "skos", "shacl", "shex" and "rdf",
+/// or null for none, which is the default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+/// `preset` has to be null or point to a NUL terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_predicate_order_preset(
+    options: *mut PrttlOptions,
+    preset: *const c_char,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.predicate_order_preset =
+                read_choice::<SpecialPredicateOrder>("predicate_order_preset", preset)?;
+            Ok(())
+        })
+    }
+}
+
+/// Sets a custom order of predicates to be used for sorting,
+/// as `len` IRIs or prefixed names,
+/// or null for none, which is the default.
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+/// `predicates` has to be null or point to `len` pointers to NUL terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_predicate_order(
+    options: *mut PrttlOptions,
+    predicates: *const *const c_char,
+    len: usize,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.predicate_order = read_list("predicate_order", predicates, len)?;
+            Ok(())
+        })
+    }
+}
+
+/// Sets the line endings of the output;
+/// one of "auto" (those of the input, the default), "lf" and "crlf".
+///
+/// # Safety
+///
+/// `options` has to come from `prttl_options_new`, and not be freed yet.
+/// `end_of_line` has to point to a NUL terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prttl_options_set_end_of_line(
+    options: *mut PrttlOptions,
+    end_of_line: *const c_char,
+) -> PrttlStatus {
+    // SAFETY: Passed on from the caller
+    unsafe {
+        update(options, |options| {
+            options.end_of_line = read_choice::<EndOfLine>("end_of_line", end_of_line)?
+                .ok_or_else(|| Failure::null_pointer("end_of_line"))?;
+            Ok(())
+        })
+    }
+}
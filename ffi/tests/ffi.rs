@@ -0,0 +1,173 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ffi::{CStr, c_char};
+use std::ptr;
+
+use prttl_ffi::error::{
+    PrttlLocation, PrttlStatus, prttl_last_error_kind, prttl_last_error_location,
+    prttl_last_error_message,
+};
+use prttl_ffi::options::{
+    prttl_options_free, prttl_options_new, prttl_options_set_indentation,
+    prttl_options_set_predicate_order_preset,
+};
+use prttl_ffi::{prttl_buffer_free, prttl_format_trig, prttl_format_turtle, prttl_version};
+
+/// Formats `input` with `format`, returning the status and the output, if any.
+fn call(
+    format: unsafe extern "C" fn(
+        *const prttl_ffi::options::PrttlOptions,
+        *const u8,
+        usize,
+        *mut *mut u8,
+        *mut usize,
+    ) -> PrttlStatus,
+    options: *const prttl_ffi::options::PrttlOptions,
+    input: &str,
+) -> (PrttlStatus, Option<String>) {
+    let mut output: *mut u8 = ptr::null_mut();
+    let mut output_len = 0;
+    // SAFETY: All pointers are valid
+    let status = unsafe {
+        format(
+            options,
+            input.as_ptr(),
+            input.len(),
+            &raw mut output,
+            &raw mut output_len,
+        )
+    };
+    if output.is_null() {
+        return (status, None);
+    }
+    // SAFETY: On success, `output` points to `output_len` bytes plus a NUL
+    let text = unsafe { CStr::from_ptr(output.cast::<c_char>()) }
+        .to_str()
+        .unwrap()
+        .to_owned();
+    assert_eq!(text.len(), output_len);
+    // SAFETY: `output` came from the formatting function
+    unsafe { prttl_buffer_free(output, output_len) };
+    (status, Some(text))
+}
+
+fn last_error_str(get: extern "C" fn() -> *const c_char) -> Option<String> {
+    let text = get();
+    // SAFETY: Non-null strings returned by prttl are valid until the next call
+    (!text.is_null()).then(|| unsafe { CStr::from_ptr(text) }.to_str().unwrap().to_owned())
+}
+
+#[test]
+fn formats_with_default_options() {
+    let (status, output) = call(
+        prttl_format_turtle,
+        ptr::null(),
+        "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n",
+    );
+    assert_eq!(status, PrttlStatus::Ok);
+    assert_eq!(
+        output.as_deref(),
+        Some("PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n")
+    );
+    assert_eq!(last_error_str(prttl_last_error_message), None);
+}
+
+#[test]
+fn formats_with_options() {
+    let options = prttl_options_new();
+    // SAFETY: `options` is fresh and the strings are NUL terminated
+    unsafe {
+        assert_eq!(
+            prttl_options_set_indentation(options, c"    ".as_ptr()),
+            PrttlStatus::Ok
+        );
+        assert_eq!(
+            prttl_options_set_predicate_order_preset(options, c"owl".as_ptr()),
+            PrttlStatus::Ok
+        );
+    }
+    let (status, output) = call(
+        prttl_format_trig,
+        options,
+        "<http://example.org/g> { <http://example.org/s> <http://example.org/p> 1 ; <http://example.org/q> 2 . }",
+    );
+    // SAFETY: `options` came from `prttl_options_new`
+    unsafe { prttl_options_free(options) };
+    assert_eq!(status, PrttlStatus::Ok);
+    assert!(
+        output
+            .unwrap()
+            .contains("\n        <http://example.org/q> 2")
+    );
+}
+
+#[test]
+fn reports_invalid_options() {
+    let options = prttl_options_new();
+    // SAFETY: `options` is fresh and the strings are NUL terminated
+    let status = unsafe { prttl_options_set_predicate_order_preset(options, c"none".as_ptr()) };
+    // SAFETY: `options` came from `prttl_options_new`
+    unsafe { prttl_options_free(options) };
+    assert_eq!(status, PrttlStatus::InvalidArgument);
+    assert_eq!(
+        last_error_str(prttl_last_error_kind).as_deref(),
+        Some("invalid-argument")
+    );
+}
+
+#[test]
+fn reports_errors_with_1based_locations() {
+    let (status, output) = call(
+        prttl_format_turtle,
+        ptr::null(),
+        "<http://example.org/s> <http://example.org/p> .",
+    );
+    assert_eq!(status, PrttlStatus::TurtleSyntax);
+    assert_eq!(output, None);
+    assert_eq!(
+        last_error_str(prttl_last_error_kind).as_deref(),
+        Some("turtle-syntax")
+    );
+    assert!(last_error_str(prttl_last_error_message).is_some());
+
+    let mut location = PrttlLocation {
+        start_line: 0,
+        start_column: 0,
+        end_line: 0,
+        end_column: 0,
+    };
+    // SAFETY: `location` is writable
+    assert!(unsafe { prttl_last_error_location(&raw mut location) });
+    assert_eq!((location.start_line, location.start_column), (1, 47));
+}
+
+#[test]
+fn reports_null_pointers() {
+    let mut output: *mut u8 = ptr::null_mut();
+    let mut output_len = 0;
+    // SAFETY: Null is allowed, and the other pointers are valid
+    let status = unsafe {
+        prttl_format_turtle(
+            ptr::null(),
+            ptr::null(),
+            0,
+            &raw mut output,
+            &raw mut output_len,
+        )
+    };
+    assert_eq!(status, PrttlStatus::NullPointer);
+    assert!(output.is_null());
+    // SAFETY: Freeing null does nothing
+    unsafe { prttl_buffer_free(ptr::null_mut(), 0) };
+}
+
+#[test]
+fn has_a_version() {
+    let version = prttl_version();
+    assert!(!version.is_null());
+    // SAFETY: The version is a valid, static C string
+    let version = unsafe { CStr::from_ptr(version) };
+    assert_eq!(version.to_str().unwrap(), prttl::VERSION);
+}
@@ -0,0 +1,48 @@
+# SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+#
+# SPDX-License-Identifier: Apache-2.0
+
+from typing import Literal, Optional, Sequence, Tuple
+
+__version__: str
+
+Preset = Literal["owl", "skos", "shacl", "shex", "rdf"]
+
+def format_turtle(
+    text: str,
+    *,
+    indentation: int = 2,
+    single_leafed_new_lines: bool = False,
+    force: bool = False,
+    generate_sorting_ids: bool = False,
+    prioritize_input_order: bool = False,
+    prtr_sorting: bool = True,
+    sparql_syntax: bool = True,
+    max_nesting: bool = True,
+    canonicalize: bool = False,
+    subject_type_order_preset: Optional[Preset] = None,
+    subject_type_order: Optional[Sequence[str]] = None,
+    predicate_order_preset: Optional[Preset] = None,
+    predicate_order: Optional[Sequence[str]] = None,
+    end_of_line: Literal["auto", "lf", "crlf"] = "auto",
+) -> str: ...
+
+class PrttlError(Exception):
+    kind: str
+    location: Optional[Tuple[int, int, int, int]]
+
+class ParseError(PrttlError): ...
+class CanonicalizationError(ParseError): ...
+class PrefixRedefinitionError(ParseError): ...
+class MultiplePrefixesForNamespaceError(ParseError): ...
+class CommentError(ParseError): ...
+class BaseRedefinitionError(ParseError): ...
+class PrefixAndBaseShareNamespaceError(ParseError): ...
+class TurtleSyntaxError(ParseError): ...
+class BaseIriError(ParseError): ...
+class RdfXmlSyntaxError(ParseError): ...
+class JsonLdSyntaxError(ParseError): ...
+class NamedGraphError(ParseError): ...
+class MultipleBasesError(ParseError): ...
+class CheckError(PrttlError): ...
+class SortingIdError(PrttlError): ...
@@ -0,0 +1,256 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Python bindings for [`prttl`],
+//! built with [maturin](https://www.maturin.rs) into the `prttl` Python module.
+//!
+//! Errors become instances of [`PrttlError`] and its subclasses,
+//! one for each kind of [`prttl::parser::Error`]
+//! and the kinds of [`prttl::error::Error`] that formatting text can produce.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use prttl::error::Error;
+use prttl::options::{EndOfLine, FormatOptions, SpecialPredicateOrder, SpecialSubjectTypeOrder};
+use prttl::parser::{self, InputFormat};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+create_exception!(
+    prttl,
+    PrttlError,
+    PyException,
+    "Base class of all errors of prttl.\n\n\
+`kind` holds a short, stable, machine-readable name of the error,\n\
+and `location` the (1-based) start line, start column, end line and end column\n\
+of where in the input it occurred, if known."
+);
+create_exception!(
+    prttl,
+    ParseError,
+    PrttlError,
+    "The input is not valid or not supported Turtle."
+);
+create_exception!(prttl, CanonicalizationError, ParseError);
+create_exception!(prttl, PrefixRedefinitionError, ParseError);
+create_exception!(prttl, MultiplePrefixesForNamespaceError, ParseError);
+create_exception!(prttl, CommentError, ParseError);
+create_exception!(prttl, BaseRedefinitionError, ParseError);
+create_exception!(prttl, PrefixAndBaseShareNamespaceError, ParseError);
+create_exception!(prttl, TurtleSyntaxError, ParseError);
+create_exception!(prttl, BaseIriError, ParseError);
+create_exception!(prttl, RdfXmlSyntaxError, ParseError);
+create_exception!(prttl, JsonLdSyntaxError, ParseError);
+create_exception!(prttl, NamedGraphError, ParseError);
+create_exception!(prttl, MultipleBasesError, ParseError);
+create_exception!(
+    prttl,
+    CheckError,
+    PrttlError,
+    "The formatted output is not equivalent to the input, which is a bug of prttl."
+);
+create_exception!(prttl, SortingIdError, PrttlError);
+
+fn parse_error(err: &parser::Error, message: String) -> PyErr {
+    match err {
+        parser::Error::Canonicalization(_) => CanonicalizationError::new_err(message),
+        parser::Error::PrefixRedefinition(_) => PrefixRedefinitionError::new_err(message),
+        parser::Error::MultiplePrefixesForNamespace(_) => {
+            MultiplePrefixesForNamespaceError::new_err(message)
+        }
+        parser::Error::Comment => CommentError::new_err(message),
+        parser::Error::BaseRedefinition => BaseRedefinitionError::new_err(message),
+        parser::Error::PrefixAndBaseShareNamespace(_) => {
+            PrefixAndBaseShareNamespaceError::new_err(message)
+        }
+        parser::Error::TurtleSyntaxError(_) => TurtleSyntaxError::new_err(message),
+        parser::Error::BaseIri(_) => BaseIriError::new_err(message),
+        parser::Error::RdfXmlSyntaxError(_) => RdfXmlSyntaxError::new_err(message),
+        parser::Error::JsonLdSyntaxError(_) => JsonLdSyntaxError::new_err(message),
+        parser::Error::NamedGraph => NamedGraphError::new_err(message),
+    }
+}
+
+/// Converts an error into the matching Python exception,
+/// with its `kind` and `location` set.
+// The variants not listed come from the parts of the library
+// that access the file system,
+// some of which only exist with its `cli` feature.
+#[allow(clippy::wildcard_enum_match_arm)]
+fn to_py_err(py: Python<'_>, err: &Error) -> PyErr {
+    let message = err.to_string();
+    let py_err = match err {
+        Error::File(_, inner) => return to_py_err(py, inner),
+        Error::ParseError(parse_err) => parse_error(parse_err, message),
+        Error::TurtleSyntaxError(_) => TurtleSyntaxError::new_err(message),
+        Error::MultipleBases => MultipleBasesError::new_err(message),
+        Error::Check(_) => CheckError::new_err(message),
+        Error::SortingIdGeneration(_) => SortingIdError::new_err(message),
+        _ => PrttlError::new_err(message),
+    };
+    let location = err.location_1based().map(|range| {
+        (
+            range.start.line,
+            range.start.column,
+            range.end.line,
+            range.end.column,
+        )
+    });
+    let value = py_err.value(py);
+    match value
+        .setattr("kind", err.kind())
+        .and_then(|()| value.setattr("location", location))
+    {
+        Ok(()) => py_err,
+        Err(attr_err) => attr_err,
+    }
+}
+
+/// Extracts the value of an option,
+/// naming the option if that fails.
+fn extract<'a, 'py, T: FromPyObject<'a, 'py>>(
+    key: &str,
+    value: &'a Bound<'py, PyAny>,
+) -> PyResult<T> {
+    value.extract::<T>().map_err(|err| {
+        let err: PyErr = err.into();
+        PyTypeError::new_err(format!("Invalid value for '{key}': {err}"))
+    })
+}
+
+/// Extracts the name of a preset or another choice,
+/// like `"owl"` for [`SpecialPredicateOrder::Owl`].
+fn extract_choice<T: FromStr>(key: &str, value: &Bound<'_, PyAny>) -> PyResult<Option<T>>
+where
+    T::Err: std::fmt::Display,
+{
+    extract::<Option<String>>(key, value)?
+        .map(|name| {
+            name.parse()
+                .map_err(|err| PyValueError::new_err(format!("Invalid value for '{key}': {err}")))
+        })
+        .transpose()
+}
+
+/// Builds the formatting options from the keyword arguments,
+/// which are named like the fields of [`FormatOptions`].
+fn parse_options(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<FormatOptions> {
+    let mut options = FormatOptions::embedded();
+    let Some(kwargs) = kwargs else {
+        return Ok(options);
+    };
+    for (key, value) in kwargs {
+        let key: String = key.extract()?;
+        match key.as_str() {
+            "indentation" => {
+                options.indentation = " ".repeat(extract::<u8>(&key, &value)?.into());
+            }
+            "single_leafed_new_lines" => {
+                options.single_leafed_new_lines = extract(&key, &value)?;
+            }
+            "force" => options.force = extract(&key, &value)?,
+            "generate_sorting_ids" => options.generate_sorting_ids = extract(&key, &value)?,
+            "prioritize_input_order" => options.prioritize_input_order = extract(&key, &value)?,
+            "prtr_sorting" => options.prtr_sorting = extract(&key, &value)?,
+            "sparql_syntax" => options.sparql_syntax = extract(&key, &value)?,
+            "max_nesting" => options.max_nesting = extract(&key, &value)?,
+            "canonicalize" => options.canonicalize = extract(&key, &value)?,
+            "subject_type_order_preset" => {
+                options.subject_type_order_preset =
+                    extract_choice::<SpecialSubjectTypeOrder>(&key, &value)?;
+            }
+            "subject_type_order" => options.subject_type_order = extract(&key, &value)?,
+            "predicate_order_preset" => {
+                options.predicate_order_preset =
+                    extract_choice::<SpecialPredicateOrder>(&key, &value)?;
+            }
+            "predicate_order" => options.predicate_order = extract(&key, &value)?,
+            "end_of_line" => {
+                options.end_of_line =
+                    extract_choice::<EndOfLine>(&key, &value)?.unwrap_or_default();
+            }
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "format_turtle() got an unexpected keyword argument '{key}'"
+                )));
+            }
+        }
+    }
+    Ok(options)
+}
+
+/// Formats Turtle text, and returns the formatted text.
+/// Text starting with a `# prttl: skip` comment is returned as it is.
+///
+/// The keyword arguments are the formatting options, with the defaults of the CLI:
+/// `indentation` (number of spaces, 2), `single_leafed_new_lines` (False),
+/// `force` (False; needed to format input with comments, which get removed),
+/// `generate_sorting_ids` (False), `prioritize_input_order` (False),
+/// `prtr_sorting` (True), `sparql_syntax` (True), `max_nesting` (True),
+/// `canonicalize` (False),
+/// `subject_type_order_preset` and `predicate_order_preset`
+/// (one of "owl", "skos", "shacl", "shex" and "rdf", or None),
+/// `subject_type_order` and `predicate_order`
+/// (lists of IRIs or prefixed names, or None),
+/// and `end_of_line` ("auto", "lf" or "crlf").
+///
+/// Raises a subclass of `PrttlError` if the text can not be formatted,
+/// like `CommentError` or `TurtleSyntaxError`.
+#[pyfunction]
+#[pyo3(signature = (text, **options))]
+fn format_turtle(
+    py: Python<'_>,
+    text: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<String> {
+    let options = Arc::new(parse_options(options)?);
+    if prttl::is_skipped(text) {
+        return Ok(text.to_owned());
+    }
+    py.detach(|| prttl::format_str_styled(text, InputFormat::Turtle, &options))
+        .map_err(|err| to_py_err(py, &err))
+}
+
+#[pymodule]
+#[pyo3(name = "prttl")]
+fn prttl_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_function(wrap_pyfunction!(format_turtle, m)?)?;
+    m.add("PrttlError", py.get_type::<PrttlError>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add(
+        "CanonicalizationError",
+        py.get_type::<CanonicalizationError>(),
+    )?;
+    m.add(
+        "PrefixRedefinitionError",
+        py.get_type::<PrefixRedefinitionError>(),
+    )?;
+    m.add(
+        "MultiplePrefixesForNamespaceError",
+        py.get_type::<MultiplePrefixesForNamespaceError>(),
+    )?;
+    m.add("CommentError", py.get_type::<CommentError>())?;
+    m.add(
+        "BaseRedefinitionError",
+        py.get_type::<BaseRedefinitionError>(),
+    )?;
+    m.add(
+        "PrefixAndBaseShareNamespaceError",
+        py.get_type::<PrefixAndBaseShareNamespaceError>(),
+    )?;
+    m.add("TurtleSyntaxError", py.get_type::<TurtleSyntaxError>())?;
+    m.add("BaseIriError", py.get_type::<BaseIriError>())?;
+    m.add("RdfXmlSyntaxError", py.get_type::<RdfXmlSyntaxError>())?;
+    m.add("JsonLdSyntaxError", py.get_type::<JsonLdSyntaxError>())?;
+    m.add("NamedGraphError", py.get_type::<NamedGraphError>())?;
+    m.add("MultipleBasesError", py.get_type::<MultipleBasesError>())?;
+    m.add("CheckError", py.get_type::<CheckError>())?;
+    m.add("SortingIdError", py.get_type::<SortingIdError>())?;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+# SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+#
+# SPDX-License-Identifier: Apache-2.0
+
+"""Smoke tests of the Python bindings; run with `pytest python/tests`."""
+
+import pytest
+
+import prttl
+
+
+def test_formats_with_default_options():
+    formatted = prttl.format_turtle("@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n")
+    assert formatted == "PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n"
+
+
+def test_formats_with_options():
+    formatted = prttl.format_turtle(
+        "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o ; ex:q 1 .\n",
+        indentation=4,
+        sparql_syntax=False,
+        end_of_line="crlf",
+    )
+    assert formatted.startswith("@prefix ex: <http://example.org/> .\r\n")
+    assert "\r\n    ex:q 1 ;\r\n" in formatted
+
+
+def test_rejects_unknown_options():
+    with pytest.raises(TypeError, match="indentaton"):
+        prttl.format_turtle("", indentaton=4)
+
+
+def test_raises_syntax_errors_with_1based_locations():
+    with pytest.raises(prttl.TurtleSyntaxError) as info:
+        prttl.format_turtle("<http://example.org/s> <http://example.org/p> .")
+    assert isinstance(info.value, prttl.ParseError)
+    assert isinstance(info.value, prttl.PrttlError)
+    assert info.value.kind == "turtle-syntax"
+    assert info.value.location == (1, 47, 1, 48)
+
+
+def test_raises_comment_errors_unless_forced():
+    text = "# A comment\n<http://example.org/s> <http://example.org/p> 1 .\n"
+    with pytest.raises(prttl.CommentError) as info:
+        prttl.format_turtle(text)
+    assert info.value.kind == "comment"
+    assert info.value.location is None
+    assert "A comment" not in prttl.format_turtle(text, force=True)
+
+
+def test_leaves_skipped_text():
+    text = "# prttl: skip\n@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n"
+    assert prttl.format_turtle(text) == text
+
+
+def test_has_a_version():
+    assert prttl.__version__
@@ -9,6 +9,7 @@ use std::collections::HashSet;
 use std::convert::Infallible;
 use std::hash::Hash;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::sync::LazyLock;
 
 use oxrdf::BlankNode;
@@ -193,7 +194,7 @@ pub enum TNamedNode<'graph> {
 }
 
 impl<'graph> TNamedNode<'graph> {
-    fn from(input: &'graph Input, named_node: NamedNodeRef<'graph>) -> Self {
+    pub(crate) fn from(input: &'graph Input, named_node: NamedNodeRef<'graph>) -> Self {
         if let Some((namespace, local_name)) = named_node
             .as_str()
             .rsplit_once('#')
@@ -690,7 +691,7 @@ impl Part for TBlankNode<'_> {
 }
 
 pub struct SortingContext<'sorting> {
-    pub options: Rc<FormatOptions>,
+    pub options: Arc<FormatOptions>,
     // pub prefixes: &'sorting Vec<(String, String)>,
     pub graph: &'sorting Graph,
     /// A cache for blank node sorting ids (`prtr::sortingId`),
@@ -901,20 +902,32 @@ fn extract_collection<'graph>(
 }
 
 fn evaluate_nestable_and_unreferenced_blank_nodes<'graph, 'tree, S: ::std::hash::BuildHasher>(
-    g_main: &'graph Graph,
+    input: &'graph Input,
     unreferenced_blank_nodes: &'tree mut HashSet<BlankNodeRef<'graph>, S>,
 ) -> HashSet<BlankNodeRef<'graph>>
 where
     'graph: 'tree,
 {
+    // Blank nodes shared with other graphs of the dataset
+    // have to keep their label.
+    let is_shared = |bn: BlankNodeRef<'_>| {
+        input
+            .dataset
+            .as_ref()
+            .is_some_and(|dataset| dataset.blank_nodes.contains(&bn.into_owned()))
+    };
     let mut subject_bns = vec![];
     let mut object_bns = vec![];
-    for triple in g_main {
+    for triple in &input.graph {
         if let NamedOrBlankNodeRef::BlankNode(bn_subj) = triple.subject {
-            subject_bns.push(bn_subj);
+            if !is_shared(bn_subj) {
+                subject_bns.push(bn_subj);
+            }
         }
         if let TermRef::BlankNode(bn_obj) = triple.object {
-            object_bns.push(bn_obj);
+            if !is_shared(bn_obj) {
+                object_bns.push(bn_obj);
+            }
         }
     }
     for subj_bn in subject_bns.iter().filter(|bn| !object_bns.contains(bn)) {
@@ -1017,7 +1030,7 @@ where
         );
     }
     let nestable_blank_nodes =
-        evaluate_nestable_and_unreferenced_blank_nodes(&input.graph, unreferenced_blank_nodes);
+        evaluate_nestable_and_unreferenced_blank_nodes(input, unreferenced_blank_nodes);
 
     let ctx = CreationContext {
         input,
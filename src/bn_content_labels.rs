@@ -0,0 +1,137 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Labeling blank nodes by their content,
+//! so the same blank node gets the same label in different versions of a graph,
+//! independent of the other blank nodes in each version.
+//!
+//! The content of a blank node are the triples it is the subject of,
+//! with the blank nodes within labeled by their content in turn.
+
+use std::collections::{HashMap, HashSet};
+
+use oxrdf::{BlankNode, Graph, NamedOrBlankNode, NamedOrBlankNodeRef, Term, TermRef, Triple};
+use sha2::{Digest, Sha256};
+
+/// The number of hex digits of the content hash used in the labels.
+const HASH_LEN: usize = 16;
+
+/// The label of a blank node derived from its content,
+/// or `None` if it can not be derived,
+/// because the blank node is on a cycle.
+fn content_label(
+    graph: &Graph,
+    blank_node: &BlankNode,
+    labels: &mut HashMap<BlankNode, Option<String>>,
+    path: &mut HashSet<BlankNode>,
+) -> Option<String> {
+    if let Some(label) = labels.get(blank_node) {
+        return label.clone();
+    }
+    if !path.insert(blank_node.clone()) {
+        return None;
+    }
+    let mut lines = Vec::new();
+    let mut on_cycle = false;
+    for triple in graph.triples_for_subject(blank_node.as_ref()) {
+        let object = if let TermRef::BlankNode(object) = triple.object {
+            if let Some(label) = content_label(graph, &object.into_owned(), labels, path) {
+                format!("_:{label}")
+            } else {
+                on_cycle = true;
+                break;
+            }
+        } else {
+            triple.object.to_string()
+        };
+        lines.push(format!("{} {object}", triple.predicate));
+    }
+    path.remove(blank_node);
+    let label = (!on_cycle && !lines.is_empty()).then(|| {
+        lines.sort_unstable();
+        let hash = format!("{:x}", Sha256::digest(lines.join("\n").as_bytes()));
+        format!("h{}", hash.chars().take(HASH_LEN).collect::<String>())
+    });
+    labels.insert(blank_node.clone(), label.clone());
+    label
+}
+
+/// The labels of the blank nodes of a graph, derived from their content.
+///
+/// Blank nodes for which this is not possible keep their label;
+/// these are the ones on cycles, the ones without any triples of their own,
+/// and those with the same content as another one.
+/// The graph should thus be canonicalized first,
+/// so these at least get stable labels.
+pub struct ContentLabels {
+    labels: HashMap<BlankNode, BlankNode>,
+}
+
+impl ContentLabels {
+    #[must_use]
+    pub fn new(graph: &Graph) -> Self {
+        let mut blank_nodes = HashSet::new();
+        for triple in graph {
+            if let NamedOrBlankNodeRef::BlankNode(subject) = triple.subject {
+                blank_nodes.insert(subject.into_owned());
+            }
+            if let TermRef::BlankNode(object) = triple.object {
+                blank_nodes.insert(object.into_owned());
+            }
+        }
+        let mut content_labels = HashMap::new();
+        for blank_node in &blank_nodes {
+            content_label(graph, blank_node, &mut content_labels, &mut HashSet::new());
+        }
+        let mut label_counts: HashMap<&str, usize> = HashMap::new();
+        for label in content_labels.values().flatten() {
+            *label_counts.entry(label).or_default() += 1;
+        }
+        let labels = blank_nodes
+            .iter()
+            .map(|blank_node| {
+                let label = content_labels
+                    .get(blank_node)
+                    .and_then(Option::as_deref)
+                    .filter(|label| label_counts.get(label) == Some(&1))
+                    .map_or_else(|| blank_node.clone(), BlankNode::new_unchecked);
+                (blank_node.clone(), label)
+            })
+            .collect();
+        Self { labels }
+    }
+
+    fn relabel(&self, blank_node: BlankNode) -> BlankNode {
+        self.labels.get(&blank_node).cloned().unwrap_or(blank_node)
+    }
+
+    /// The subject with its blank node relabeled, if it is one.
+    #[must_use]
+    pub fn subject(&self, subject: NamedOrBlankNodeRef<'_>) -> NamedOrBlankNode {
+        if let NamedOrBlankNodeRef::BlankNode(blank_node) = subject {
+            return self.relabel(blank_node.into_owned()).into();
+        }
+        subject.into_owned()
+    }
+
+    /// The object with its blank node relabeled, if it is one.
+    #[must_use]
+    pub fn object(&self, object: TermRef<'_>) -> Term {
+        if let TermRef::BlankNode(blank_node) = object {
+            return self.relabel(blank_node.into_owned()).into();
+        }
+        object.into_owned()
+    }
+
+    /// All the triples of the graph, with their blank nodes relabeled.
+    pub fn triples<'a>(&'a self, graph: &'a Graph) -> impl Iterator<Item = Triple> + 'a {
+        graph.iter().map(|triple| {
+            Triple::new(
+                self.subject(triple.subject),
+                triple.predicate.into_owned(),
+                self.object(triple.object),
+            )
+        })
+    }
+}
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::LinkedList;
 use std::collections::hash_map::Entry;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use oxrdf::BlankNodeRef;
 use oxrdf::NamedOrBlankNodeRef;
@@ -23,7 +23,7 @@ type SortingId = u32;
 /// A cache for blank node sorting ids (`prtr::sortingId`).
 /// We use a cache for performance reasons.
 pub struct Cache<'graph> {
-    options: Rc<FormatOptions>,
+    options: Arc<FormatOptions>,
     input: &'graph Input,
     /// The sorting ID associated with each blank node.
     bn_sorting_ids: HashMap<BlankNodeRef<'graph>, Option<SortingId>>,
@@ -36,11 +36,14 @@ pub enum Error {
 but already assigned sorting IDs are not in order"
     )]
     IdsNotInOrder,
+
+    #[error("Multiple prtr:sortingId values for blank node {0}. Please reduce to one.")]
+    MultipleSortingIds(String),
 }
 
 impl<'graph> Cache<'graph> {
     #[must_use]
-    pub fn new<'new>(input: &'new Input, options: Rc<FormatOptions>) -> Self
+    pub fn new<'new>(input: &'new Input, options: Arc<FormatOptions>) -> Self
     where
         'new: 'graph,
     {
@@ -51,6 +54,29 @@ impl<'graph> Cache<'graph> {
         }
     }
 
+    /// Checks that no blank node has more then one sorting ID,
+    /// as we could not tell which one to sort by.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MultipleSortingIds`]
+    pub fn check_unique(&self) -> Result<(), Error> {
+        let graph = self
+            .input
+            .dataset
+            .as_ref()
+            .map_or(&self.input.graph, |dataset| &dataset.sorting_ids);
+        let mut seen = HashSet::new();
+        for triple in graph.triples_for_predicate(*prtr::SORTING_ID) {
+            if let NamedOrBlankNodeRef::BlankNode(bn) = triple.subject {
+                if !seen.insert(bn) {
+                    return Err(Error::MultipleSortingIds(bn.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
     #[must_use]
     pub fn fetch_prtr_sorting_id<'sorting>(&mut self, bn: &BlankNodeRef<'graph>) -> Option<u32>
     where
@@ -59,42 +85,37 @@ impl<'graph> Cache<'graph> {
         match self.bn_sorting_ids./*borrow_mut().*/entry(*bn) {
             Entry::Occupied(entry) => *entry.get(),
             Entry::Vacant(entry) => {
+                // Blank nodes are shared between the graphs of a dataset,
+                // so their sorting ID may be given in any of them.
                 let sorting_ids = self
                     .input
-                    .graph
+                    .dataset
+                    .as_ref()
+                    .map_or(&self.input.graph, |dataset| &dataset.sorting_ids)
                     .objects_for_subject_predicate(
                         NamedOrBlankNodeRef::BlankNode(*bn),
                         *prtr::SORTING_ID,
                     )
                     .collect::<Vec<_>>();
 
-                let sorting_id_opt = if sorting_ids.is_empty() {
-                    None
-                } else if sorting_ids.len() > 1 {
-                    tracing::error!(
-                        "Multiple prtr:sortingId values for blank node {bn}. Please reduce to one.",
-                    );
-                    std::process::exit(2);
-                    None
-                } else {
-                    sorting_ids.first().and_then(|sorting_id_term| {
-                        if let TermRef::Literal(sorting_id_literal) = sorting_id_term {
-                            sorting_id_literal
-                                .value()
-                                .parse()
-                                .map_err(|err| {
-                                    tracing::warn!(
-                                        "Failed to parse prtr:sortingId value ('{}') as u32: {err}",
-                                        sorting_id_literal.value()
-                                    );
-                                    err
-                                })
-                                .ok() // TODO Maybe panic! instead?
-                        } else {
-                            None
-                        }
-                    })
-                };
+                // Multiple values are rejected up front, by `Self::check_unique`
+                let sorting_id_opt = sorting_ids.first().and_then(|sorting_id_term| {
+                    if let TermRef::Literal(sorting_id_literal) = sorting_id_term {
+                        sorting_id_literal
+                            .value()
+                            .parse()
+                            .map_err(|err| {
+                                tracing::warn!(
+                                    "Failed to parse prtr:sortingId value ('{}') as u32: {err}",
+                                    sorting_id_literal.value()
+                                );
+                                err
+                            })
+                            .ok() // TODO Maybe panic! instead?
+                    } else {
+                        None
+                    }
+                });
 
                 entry.insert(sorting_id_opt);
                 sorting_id_opt
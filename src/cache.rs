@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! An on-disk record of content that is known to be formatted already,
+//! which allows to skip parsing and formatting it again.
+//!
+//! Each entry is an empty file,
+//! named after a hash of the content,
+//! the format of the content
+//! and the [`FormatOptions`] in effect,
+//! in a directory named after the version of this tool.
+//! Changing any of these thus automatically invalidates the entry,
+//! and the entries of previous versions can be removed all at once.
+//!
+//! The cache is only used if asked for,
+//! see [`crate::options::RunOptions::cache_dir`].
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::VERSION;
+use crate::options::{FormatOptions, PartialFormatOptions};
+use crate::parser::InputFormat;
+
+/// The directory the CLI stores the cache in by default,
+/// relative to the working directory.
+pub const DEFAULT_DIR: &str = ".prttl-cache";
+
+/// Written into the cache directory,
+/// so it does not have to be added to `.gitignore` files manually.
+const GITIGNORE_CONTENT: &str = "# Automatically created by prttl\n*\n";
+
+/// A cache of content that is known to be formatted already.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    #[must_use]
+    pub const fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Creates the key for the given content,
+    /// formatted with the given options.
+    ///
+    /// Returns `None` if the options can not be serialized,
+    /// which should never happen.
+    fn key(content: &str, input_format: InputFormat, options: &FormatOptions) -> Option<String> {
+        let options_json = serde_json::to_string(&PartialFormatOptions::from(options)).ok()?;
+        let mut hasher = blake3::Hasher::new();
+        for part in [&format!("{input_format:?}"), &options_json, content] {
+            // The length prevents ambiguities between the parts
+            hasher.update(&part.len().to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        Some(hasher.finalize().to_hex().to_string())
+    }
+
+    fn version_dir(&self) -> PathBuf {
+        self.dir.join(VERSION)
+    }
+
+    fn entry(&self, key: &str) -> PathBuf {
+        self.version_dir().join(key)
+    }
+
+    /// Whether the content is known to be formatted already.
+    #[must_use]
+    pub fn is_formatted(
+        &self,
+        content: &str,
+        input_format: InputFormat,
+        options: &FormatOptions,
+    ) -> bool {
+        Self::key(content, input_format, options).is_some_and(|key| self.entry(&key).is_file())
+    }
+
+    /// Records that the content is formatted.
+    ///
+    /// Failing to do so is not an error,
+    /// as it only means we have to do some work again next time;
+    /// it gets logged though.
+    pub fn insert(&self, content: &str, input_format: InputFormat, options: &FormatOptions) {
+        let Some(key) = Self::key(content, input_format, options) else {
+            return;
+        };
+        if let Err(err) = self
+            .ensure_dir()
+            .and_then(|()| fs::write(self.entry(&key), ""))
+        {
+            tracing::warn!(
+                "Failed to write to the cache in '{}': {err}",
+                self.dir.display()
+            );
+        }
+    }
+
+    fn ensure_dir(&self) -> io::Result<()> {
+        if !self.dir.is_dir() {
+            fs::create_dir_all(&self.dir)?;
+            fs::write(self.dir.join(".gitignore"), GITIGNORE_CONTENT)?;
+        }
+        fs::create_dir_all(self.version_dir())
+    }
+}
@@ -2,38 +2,69 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{path::PathBuf, sync::LazyLock};
+use std::{io, num::NonZeroUsize, path::PathBuf, sync::LazyLock};
 
-use clap::{Arg, ArgAction, Command, ValueHint, command, crate_name, value_parser};
+use clap::{
+    Arg, ArgAction, ArgMatches, Command, ValueHint, command, crate_name, error::ErrorKind,
+    parser::ValueSource, value_parser,
+};
+use clap_complete::Shell;
 use cli_utils::logging;
 use const_format::formatcp;
-use prttl::options::{FormatOptions, SpecialPredicateOrder, SpecialSubjectTypeOrder};
+use prttl::config::{self, Resolver};
+use prttl::options::{
+    EndOfLine, FormatOptions, PartialFormatOptions, RunOptions, SpecialPredicateOrder,
+    SpecialSubjectTypeOrder,
+};
+use prttl::report::{ColorChoice, ReportFormat};
 use thiserror::Error;
 use tracing_subscriber::filter::LevelFilter;
 
+pub const A_L_BACKUP: &str = "backup";
+pub const A_L_CACHE: &str = "cache";
 pub const A_L_CANONICALIZE: &str = "canonicalize";
 // pub const A_S_CANONICALIZE: char = 'C';
 pub const A_L_CHECK: &str = "check";
 pub const A_S_CHECK: char = 'c';
+pub const A_L_COLOR: &str = "color";
+pub const A_L_CONVERT: &str = "convert";
+pub const A_L_END_OF_LINE: &str = "end-of-line";
+pub const A_L_EXCLUDE: &str = "exclude";
+pub const A_L_FOLLOW_SYMLINKS: &str = "follow-symlinks";
 pub const A_L_FORCE: &str = "force";
 pub const A_S_FORCE: char = 'f';
 pub const A_L_GENERATE_PRTR_SORTING_IDS: &str = "generate-sorting-ids";
 pub const A_S_GENERATE_PRTR_SORTING_IDS: char = 'g';
+pub const A_L_INCLUDE: &str = "include";
 pub const A_L_INDENTATION: &str = "indentation";
 pub const A_S_INDENTATION: char = 'i';
+pub const A_L_JOBS: &str = "jobs";
+pub const A_S_JOBS: char = 'j';
 // pub const A_L_INPUT: &str = "input";
 // pub const A_S_INPUT: char = 'I';
 pub const A_L_LABEL_ALL_BLANK_NODES: &str = "label-all-blank-nodes";
 pub const A_S_LABEL_ALL_BLANK_NODES: char = 'l';
+pub const A_L_LISTEN: &str = "listen";
+pub const A_L_LIST_DIFFERENT: &str = "list-different";
+pub const A_S_LIST_DIFFERENT: char = 'L';
+pub const A_L_MAX_BODY_SIZE: &str = "max-body-size";
+pub const A_L_NO_CACHE: &str = "no-cache";
 pub const A_L_NO_PRTR_SORTING: &str = "no-prtr-sorting";
 // pub const A_S_NO_PRTR_SORTING: char = 'p';
 pub const A_L_NO_SPARQL_SYNTAX: &str = "no-sparql-syntax";
 // pub const A_S_NO_SPARQL_SYNTAX: char = 's';
+pub const A_L_OUT_DIR: &str = "out-dir";
+pub const A_L_OUTPUT: &str = "output";
+pub const A_S_OUTPUT: char = 'o';
 pub const A_L_PREDICATE_ORDER: &str = "pred-order";
 pub const A_L_PREDICATE_ORDER_PRESET: &str = "pred-order-preset";
+pub const A_L_PRINT_CONFIG: &str = "print-config";
+pub const A_L_REPORT_FORMAT: &str = "report-format";
 pub const A_L_PRIORITIZE_INPUT_ORDER: &str = "prioritize-input-order";
+pub const A_L_STAGED: &str = "staged";
 pub const A_L_SINGLE_LEAFED_NEW_LINES: &str = "single-leafed-new-lines";
 pub const A_S_SINGLE_LEAFED_NEW_LINES: char = 'n';
+pub const A_L_STDIN_FILEPATH: &str = "stdin-filepath";
 pub const A_L_SUBJECT_TYPE_ORDER: &str = "subj-type-order";
 pub const A_L_SUBJECT_TYPE_ORDER_PRESET: &str = "subj-type-order-preset";
 pub const A_L_QUIET: &str = "quiet";
@@ -42,8 +73,43 @@ pub const A_L_VERBOSE: &str = "verbose";
 pub const A_S_VERBOSE: char = 'v';
 pub const A_L_VERSION: &str = "version";
 pub const A_S_VERSION: char = 'V';
+pub const A_L_WATCH: &str = "watch";
 pub const A_L_SRC: &str = "src";
+pub const A_L_SHELL: &str = "shell";
+pub const A_L_MERGE_BASE: &str = "base";
+pub const A_L_MERGE_OURS: &str = "ours";
+pub const A_L_MERGE_THEIRS: &str = "theirs";
+pub const A_L_MERGE_PATH: &str = "path";
+pub const A_L_PRINT_GIT_CONFIG: &str = "print-git-config";
+pub const A_L_DIFF_FILES: &str = "files";
 
+pub const SC_COMPLETIONS: &str = "completions";
+pub const SC_MAN: &str = "man";
+pub const SC_MERGE_DRIVER: &str = "merge-driver";
+pub const SC_DIFF: &str = "diff";
+pub const SC_LSP: &str = "lsp";
+pub const SC_SERVE: &str = "serve";
+
+/// The number of arguments git hands to an external diff driver:
+/// path old-file old-hex old-mode new-file new-hex new-mode
+const GIT_EXTERNAL_DIFF_NUM_ARGS: usize = 7;
+/// The number of arguments git hands to an external diff driver for a renamed file,
+/// which additionally are: new-path rename-info
+const GIT_EXTERNAL_DIFF_RENAME_NUM_ARGS: usize = 9;
+
+/// How to install the merge driver in a git repository.
+const MERGE_DRIVER_GIT_CONFIG: &str = formatcp!(
+    "# In .gitattributes:
+*.ttl merge={name}
+
+# In .git/config (or ~/.gitconfig, for all repositories):
+[merge \"{name}\"]
+\tname = {name} triple-level Turtle merge
+\tdriver = {name} {SC_MERGE_DRIVER} %O %A %B %P",
+    name = crate_name!()
+);
+
+pub const DEFAULT_BACKUP_SUFFIX: &str = "~";
 pub const DEFAULT_INDENTATION: u8 = 2;
 static DEFAULT_INDENTATION_STR: LazyLock<String> =
     LazyLock::new(|| DEFAULT_INDENTATION.to_string());
@@ -52,6 +118,38 @@ static DEFAULT_INDENTATION_STR: LazyLock<String> =
 // #[arg()]
 // src: Vec<PathBuf>,
 
+fn arg_backup() -> Arg {
+    Arg::new(A_L_BACKUP)
+        .help(formatcp!(
+            "Keep the previous content of each overwritten file, \
+in a file with SUFFIX appended to its name [default SUFFIX: '{DEFAULT_BACKUP_SUFFIX}']"
+        ))
+        .num_args(0..=1)
+        .require_equals(true)
+        .default_missing_value(DEFAULT_BACKUP_SUFFIX)
+        .value_name("SUFFIX")
+        .value_hint(ValueHint::Other)
+        .value_parser(value_parser!(String))
+        .long(A_L_BACKUP)
+        .action(ArgAction::Set)
+}
+
+fn arg_cache() -> Arg {
+    Arg::new(A_L_CACHE)
+        .help(formatcp!(
+            "Record the content that is formatted already in DIR instead of '{}', \
+so it can be skipped on later runs",
+            prttl::cache::DEFAULT_DIR
+        ))
+        .num_args(1)
+        .require_equals(true)
+        .value_name("DIR")
+        .value_hint(ValueHint::DirPath)
+        .value_parser(value_parser!(PathBuf))
+        .long(A_L_CACHE)
+        .action(ArgAction::Set)
+}
+
 fn arg_canonicalize() -> Arg {
     Arg::new(A_L_CANONICALIZE)
         .help("Whether to canonicalize the input before formatting")
@@ -76,6 +174,85 @@ if it already applies this tools format",
         .long(A_L_CHECK)
 }
 
+fn arg_color() -> Arg {
+    Arg::new(A_L_COLOR)
+        .help("When to color the patches printed in check mode")
+        .long_help(
+            "When to color the patches printed in check mode. \
+'auto' colors them only if stdout is a terminal.",
+        )
+        .num_args(1)
+        .long(A_L_COLOR)
+        .value_name("WHEN")
+        .value_parser(value_parser!(ColorChoice))
+        .default_value("auto")
+        .action(ArgAction::Set)
+}
+
+fn arg_convert() -> Arg {
+    Arg::new(A_L_CONVERT)
+        .help("Convert other RDF serializations to Turtle, instead of formatting Turtle files")
+        .long_help(
+            "Convert files in other RDF serializations \
+(N-Triples '.nt', RDF/XML '.rdf'/'.owl' and JSON-LD '.jsonld') \
+to formatted Turtle, instead of formatting Turtle files. \
+The result is written to a '.ttl' file next to each source, \
+unless --output or --out-dir is given. \
+Prefixes declared in the source are kept. \
+Already existing files are only overwritten \
+if --force is given as well.",
+        )
+        .action(ArgAction::SetTrue)
+        .long(A_L_CONVERT)
+        .conflicts_with(A_L_CHECK)
+        .conflicts_with(A_L_LIST_DIFFERENT)
+}
+
+fn arg_end_of_line() -> Arg {
+    Arg::new(A_L_END_OF_LINE)
+        .help("Which line endings to write")
+        .long_help(
+            "Which line endings to write. \
+'auto' keeps the style of the input, \
+as found at the end of its first line. \
+A byte order mark at the start of the input is always kept.",
+        )
+        .num_args(1)
+        .long(A_L_END_OF_LINE)
+        .value_name("STYLE")
+        .value_parser(value_parser!(EndOfLine))
+        .default_value("auto")
+        .action(ArgAction::Set)
+}
+
+fn arg_exclude() -> Arg {
+    Arg::new(A_L_EXCLUDE)
+        .help("Skip files in source directories that match this glob")
+        .long_help(formatcp!(
+            "Skip files and directories within source directories \
+that match this glob, relative to the source directory. \
+This is in addition to the ones ignored by '.gitignore' and '{}' files. \
+May be given multiple times.",
+            prttl::walk::IGNORE_FILE_NAME
+        ))
+        .num_args(1)
+        .long(A_L_EXCLUDE)
+        .value_name("GLOB")
+        .action(ArgAction::Append)
+}
+
+fn arg_follow_symlinks() -> Arg {
+    Arg::new(A_L_FOLLOW_SYMLINKS)
+        .help("Follow symbolic links when searching source directories")
+        .long_help(
+            "Follow symbolic links when searching source directories. \
+Without this, symbolic links are skipped. \
+File system loops are detected and skipped.",
+        )
+        .action(ArgAction::SetTrue)
+        .long(A_L_FOLLOW_SYMLINKS)
+}
+
 fn arg_force() -> Arg {
     Arg::new(A_L_FORCE)
         .help(
@@ -143,6 +320,69 @@ fn arg_indentation() -> Arg {
 //         .default_value("-")
 // }
 
+fn arg_include() -> Arg {
+    Arg::new(A_L_INCLUDE)
+        .help("Only format files in source directories that match this glob")
+        .long_help(formatcp!(
+            "Only format files within source directories \
+that match this glob, relative to the source directory. \
+Files ignored by '.gitignore' and '{}' files, \
+as well as hidden ones, are still skipped. \
+Files given directly as a source are always formatted. \
+May be given multiple times.",
+            prttl::walk::IGNORE_FILE_NAME
+        ))
+        .num_args(1)
+        .long(A_L_INCLUDE)
+        .value_name("GLOB")
+        .action(ArgAction::Append)
+}
+
+fn arg_jobs() -> Arg {
+    Arg::new(A_L_JOBS)
+        .help("How many files to process concurrently")
+        .long_help(
+            "How many files to process concurrently. \
+Defaults to the number of CPUs available.",
+        )
+        .num_args(1)
+        .short(A_S_JOBS)
+        .long(A_L_JOBS)
+        .value_name("NUM")
+        .value_parser(value_parser!(u16).range(1..))
+        .action(ArgAction::Set)
+}
+
+fn arg_list_different() -> Arg {
+    Arg::new(A_L_LIST_DIFFERENT)
+        .help("Only print the paths of the files that need formatting")
+        .long_help(
+            "Only print the paths of the files that need formatting, \
+instead of a patch for each of them. \
+This implies --check.",
+        )
+        .action(ArgAction::SetTrue)
+        .short(A_S_LIST_DIFFERENT)
+        .long(A_L_LIST_DIFFERENT)
+}
+
+fn arg_no_cache() -> Arg {
+    Arg::new(A_L_NO_CACHE)
+        .help(formatcp!(
+            "Do not use the cache in '{}'",
+            prttl::cache::DEFAULT_DIR
+        ))
+        .long_help(formatcp!(
+            "Do not use the cache in '{}' (or the one given with --{A_L_CACHE}), \
+which records the content that is formatted already, \
+so it can be skipped on later runs.",
+            prttl::cache::DEFAULT_DIR
+        ))
+        .action(ArgAction::SetTrue)
+        .conflicts_with(A_L_CACHE)
+        .long(A_L_NO_CACHE)
+}
+
 fn arg_no_prtr_sorting() -> Arg {
     Arg::new(A_L_NO_PRTR_SORTING)
         .help(
@@ -192,6 +432,50 @@ PREFIX foaf: <http://xmlns.com/foaf/0.1/> \
         .long(A_L_NO_SPARQL_SYNTAX)
 }
 
+fn arg_output() -> Arg {
+    Arg::new(A_L_OUTPUT)
+        .help("Write the formatted content to this file, instead of back to the input file")
+        .long_help(
+            "Write the formatted content to this file, \
+instead of back to the input file (or to stdout). \
+This requires a single input file. \
+An already existing file is only overwritten \
+if --force is given as well.",
+        )
+        .num_args(1)
+        .short(A_S_OUTPUT)
+        .long(A_L_OUTPUT)
+        .action(ArgAction::Set)
+        .value_hint(ValueHint::FilePath)
+        .value_name("FILE")
+        .value_parser(value_parser!(PathBuf))
+        .conflicts_with(A_L_OUT_DIR)
+        .conflicts_with(A_L_CHECK)
+        .conflicts_with(A_L_LIST_DIFFERENT)
+}
+
+fn arg_out_dir() -> Arg {
+    Arg::new(A_L_OUT_DIR)
+        .help("Write the formatted content into this directory, instead of back to the input files")
+        .long_help(
+            "Write the formatted content into this directory, \
+instead of back to the input files. \
+The directory structure of the input is mirrored, \
+relative to each source directory; \
+files given directly as a source end up directly in this directory. \
+Already existing files are only overwritten \
+if --force is given as well.",
+        )
+        .num_args(1)
+        .long(A_L_OUT_DIR)
+        .action(ArgAction::Set)
+        .value_hint(ValueHint::DirPath)
+        .value_name("DIR")
+        .value_parser(value_parser!(PathBuf))
+        .conflicts_with(A_L_CHECK)
+        .conflicts_with(A_L_LIST_DIFFERENT)
+}
+
 fn arg_predicate_order() -> Arg {
     Arg::new(A_L_PREDICATE_ORDER)
         .help(
@@ -225,6 +509,22 @@ Only direct matches are considered; meaning: No type inference is conducted.",
         .action(ArgAction::Set)
 }
 
+fn arg_print_config() -> Arg {
+    Arg::new(A_L_PRINT_CONFIG)
+        .help("Print the options in effect for each source file and exit")
+        .long_help(formatcp!(
+            "Print the formatting options in effect for each source file and exit. \
+These are combined from the defaults, \
+the '{}' configuration files found in the directory of each file \
+and its parent directories, \
+and the options given on the command-line, \
+which take precedence.",
+            config::FILE_NAME
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_PRINT_CONFIG)
+}
+
 fn arg_prioritize_input_order() -> Arg {
     Arg::new(A_L_PRIORITIZE_INPUT_ORDER)
         .help("Prioritize maintaining the input order vs keeping already assigned sorting IDs")
@@ -259,6 +559,27 @@ how should we do it?
         .long(A_L_PRIORITIZE_INPUT_ORDER)
 }
 
+fn arg_report_format() -> Arg {
+    Arg::new(A_L_REPORT_FORMAT)
+        .help("How to write the report about the processed files to stdout")
+        .long_help(
+            "How to write the report about the processed files to stdout. \
+'human' prints a patch for each file that needs formatting, \
+'json' prints one record per file with its status, \
+the kind and location of an error and the patch, if any, \
+and 'sarif' prints a SARIF v2.1.0 log for code-scanning UIs. \
+When reading from stdin without --output, \
+the report goes to stderr instead, \
+as stdout is taken by the formatted content.",
+        )
+        .num_args(1)
+        .long(A_L_REPORT_FORMAT)
+        .value_name("FORMAT")
+        .value_parser(value_parser!(ReportFormat))
+        .default_value("human")
+        .action(ArgAction::Set)
+}
+
 fn arg_single_entry_on_new_line() -> Arg {
     Arg::new(A_L_SINGLE_LEAFED_NEW_LINES)
         .help("Whether to move a single/lone predicate-object pair or object alone onto a new line")
@@ -267,6 +588,25 @@ fn arg_single_entry_on_new_line() -> Arg {
         .long(A_L_SINGLE_LEAFED_NEW_LINES)
 }
 
+fn arg_stdin_filepath() -> Arg {
+    Arg::new(A_L_STDIN_FILEPATH)
+        .help("Path to assume for the content read from stdin")
+        .long_help(formatcp!(
+            "Path to assume for the content read from stdin \
+(when the source is '{}'). \
+It is used for error messages \
+and to look up configuration files; \
+the file its self is never accessed.",
+            prttl::STDIN_SOURCE
+        ))
+        .num_args(1)
+        .long(A_L_STDIN_FILEPATH)
+        .action(ArgAction::Set)
+        .value_hint(ValueHint::FilePath)
+        .value_name("FILE")
+        .value_parser(value_parser!(PathBuf))
+}
+
 fn arg_subject_type_order() -> Arg {
     Arg::new(A_L_SUBJECT_TYPE_ORDER)
         .help(
@@ -317,6 +657,43 @@ fn arg_verbose() -> Arg {
         .action(ArgAction::SetTrue)
 }
 
+fn arg_watch() -> Arg {
+    Arg::new(A_L_WATCH)
+        .help("Keep watching the sources, and format files whenever they change")
+        .long_help(
+            "Keep watching the sources, and format files whenever they change. \
+Files that fail to be formatted are reported, \
+and watching continues. \
+When one of the configuration files in use changes - \
+also one in a directory above the sources - \
+all files are formatted again.",
+        )
+        .action(ArgAction::SetTrue)
+        .long(A_L_WATCH)
+        .conflicts_with(A_L_PRINT_CONFIG)
+}
+
+fn arg_staged() -> Arg {
+    Arg::new(A_L_STAGED)
+        .help("Format the Turtle and TriG files staged in git, as a pre-commit hook would")
+        .long_help(formatcp!(
+            "Format the Turtle and TriG files staged in git, as a pre-commit hook would. \
+The staged content is formatted and staged again; \
+the working copy is only written to if it has no unstaged changes. \
+If sources are given, they restrict the files to format, as git pathspecs. \
+Combined with --{A_L_CHECK}, nothing is written."
+        ))
+        .action(ArgAction::SetTrue)
+        .long(A_L_STAGED)
+        .conflicts_with_all([
+            A_L_CONVERT,
+            A_L_OUTPUT,
+            A_L_OUT_DIR,
+            A_L_PRINT_CONFIG,
+            A_L_WATCH,
+        ])
+}
+
 fn arg_version() -> Arg {
     Arg::new(A_L_VERSION)
         .help(formatcp!(
@@ -331,15 +708,164 @@ to really only output the version string."
 
 fn arg_src() -> Arg {
     Arg::new(A_L_SRC)
-        .help("Source RDF file(s) or director(y|ies) containing Turtle files to format")
+        .help(formatcp!(
+            "Source RDF file(s) or director(y|ies) containing Turtle or TriG files to format; \
+'{}' for stdin (the result is written to stdout)",
+            prttl::STDIN_SOURCE
+        ))
         .num_args(1..)
         .value_name("FILE_OR_DIR")
         .value_hint(ValueHint::Other)
         .value_parser(value_parser!(PathBuf))
-        .required_unless_present(A_L_VERSION)
+        .required_unless_present_any([A_L_VERSION, A_L_STAGED])
         .action(ArgAction::Set)
 }
 
+fn subcommand_completions() -> Command {
+    Command::new(SC_COMPLETIONS)
+        .about("Print the shell completion script for the given shell")
+        .arg(
+            Arg::new(A_L_SHELL)
+                .help("The shell to generate the completion script for")
+                .value_parser(value_parser!(Shell))
+                .required(true)
+                .action(ArgAction::Set),
+        )
+}
+
+fn subcommand_man() -> Command {
+    Command::new(SC_MAN).about("Print the man page, in roff format")
+}
+
+fn subcommand_merge_driver() -> Command {
+    let arg_file_version = |id: &'static str, value_name: &'static str, help: &'static str| {
+        Arg::new(id)
+            .help(help)
+            .value_name(value_name)
+            .value_hint(ValueHint::FilePath)
+            .value_parser(value_parser!(PathBuf))
+            .required_unless_present(A_L_PRINT_GIT_CONFIG)
+            .action(ArgAction::Set)
+    };
+    Command::new(SC_MERGE_DRIVER)
+        .about("Merge three versions of a Turtle file at the triple level, as a git merge driver")
+        .long_about(formatcp!(
+            "Merge three versions of a Turtle file at the triple level, as a git merge driver. \
+The merged and formatted result is written to the file of our version. \
+Conflicts - a value changed on one side and changed differently or removed on the other - \
+are reported and left out of the formatted result. \
+Instead, they are appended to it as N-Triples statements \
+between git-style conflict markers (ours, base and theirs), \
+and make this exit unsuccessfully, so git marks the file as conflicted. \
+Use --{A_L_PRINT_GIT_CONFIG} to see how to install it."
+        ))
+        .arg(
+            Arg::new(A_L_PRINT_GIT_CONFIG)
+                .help("Print the git configuration to install this merge driver, and exit")
+                .long(A_L_PRINT_GIT_CONFIG)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(arg_file_version(
+            A_L_MERGE_BASE,
+            "BASE",
+            "The common ancestor version (%O)",
+        ))
+        .arg(arg_file_version(
+            A_L_MERGE_OURS,
+            "OURS",
+            "Our version (%A), which gets replaced with the result",
+        ))
+        .arg(arg_file_version(
+            A_L_MERGE_THEIRS,
+            "THEIRS",
+            "Their version (%B)",
+        ))
+        .arg(
+            Arg::new(A_L_MERGE_PATH)
+                .help(
+                    "The path of the file in the repository (%P), to resolve the configuration for",
+                )
+                .value_name("PATH")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(value_parser!(PathBuf))
+                .action(ArgAction::Set),
+        )
+}
+
+fn subcommand_diff() -> Command {
+    Command::new(SC_DIFF)
+        .about("Show the triples removed and added between two RDF files, grouped by subject")
+        .long_about(formatcp!(
+            "Show the triples removed and added between two RDF files, grouped by subject. \
+Blank nodes are matched by their content, not by their label. \
+It can also be used as a git external diff driver, \
+in which case git hands it {GIT_EXTERNAL_DIFF_NUM_ARGS} \
+(or {GIT_EXTERNAL_DIFF_RENAME_NUM_ARGS} for renamed files) arguments; \
+to do so, add '*.ttl diff={name}' to .gitattributes, \
+and this to .git/config: \
+[diff \"{name}\"] command = {name} {SC_DIFF}",
+            name = crate_name!()
+        ))
+        .arg(
+            Arg::new(A_L_DIFF_FILES)
+                .help("The old and the new file")
+                .num_args(2..=GIT_EXTERNAL_DIFF_RENAME_NUM_ARGS)
+                .value_name("FILE")
+                .value_hint(ValueHint::FilePath)
+                .value_parser(value_parser!(PathBuf))
+                .required(true)
+                .action(ArgAction::Set),
+        )
+}
+
+fn subcommand_lsp() -> Command {
+    Command::new(SC_LSP)
+        .about("Run a language server on stdin and stdout, for formatting from within editors")
+        .long_about(
+            "Run a Language Server Protocol server on stdin and stdout, \
+for formatting from within editors. \
+It formats documents as minimal text edits, \
+and reports errors - like syntax errors and comments - as diagnostics. \
+The formatting options are resolved for each document, \
+the same way as for files given on the command-line.",
+        )
+}
+
+fn subcommand_serve() -> Command {
+    Command::new(SC_SERVE)
+        .about("Serve formatting over HTTP, as a local sidecar for editors and other tools")
+        .long_about(
+            "Serve formatting over HTTP, as a local sidecar for editors and other tools. \
+POST Turtle - or TriG, with 'Content-Type: application/trig' - to /format, \
+and get the formatted text back. \
+Formatting options can be given in the query, \
+like '/format?indentation=4&predicate-order=rdf:type,rdfs:label', \
+with the same names and values as in configuration files. \
+With 'Content-Type: application/json', the body is an object \
+with the 'text' to format, and optionally its 'format' and 'options', \
+and the answer is an object with the formatted 'text'. \
+Errors are answered as JSON, with their kind, message and location. \
+GET /health tells whether the service is up.",
+        )
+        .arg(
+            Arg::new(A_L_LISTEN)
+                .help("The address and port to listen on")
+                .long(A_L_LISTEN)
+                .value_name("ADDRESS:PORT")
+                .default_value(prttl::serve::DEFAULT_LISTEN)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new(A_L_MAX_BODY_SIZE)
+                .help("The maximum size of a request body, in bytes")
+                .long(A_L_MAX_BODY_SIZE)
+                .value_name("BYTES")
+                .value_parser(value_parser!(usize))
+                .default_value(formatcp!("{}", prttl::serve::DEFAULT_MAX_BODY_SIZE))
+                .action(ArgAction::Set),
+        )
+}
+
 fn args_matcher() -> Command {
     command!()
         .about("Pretty prints RDF/Turtle files")
@@ -360,26 +886,51 @@ More about this: \
         .bin_name(clap::crate_name!())
         .help_expected(true)
         .disable_version_flag(true)
+        .arg(arg_backup())
+        .arg(arg_cache())
         .arg(arg_canonicalize())
         .arg(arg_check())
+        .arg(arg_color())
+        .arg(arg_convert())
+        .arg(arg_end_of_line())
+        .arg(arg_exclude())
+        .arg(arg_follow_symlinks())
         .arg(arg_force())
         .arg(arg_generate_sorting_ids())
         .arg(arg_label_all_blank_nodes())
+        .arg(arg_list_different())
+        .arg(arg_include())
         .arg(arg_indentation())
+        .arg(arg_jobs())
         // .arg(arg_input())
-        // .arg(arg_output())
+        .arg(arg_output())
+        .arg(arg_out_dir())
+        .arg(arg_no_cache())
         .arg(arg_no_prtr_sorting())
         .arg(arg_no_sparql_syntax())
         .arg(arg_predicate_order())
         .arg(arg_predicate_order_preset())
+        .arg(arg_print_config())
         .arg(arg_prioritize_input_order())
+        .arg(arg_report_format())
         .arg(arg_single_entry_on_new_line())
+        .arg(arg_staged())
+        .arg(arg_stdin_filepath())
         .arg(arg_subject_type_order())
         .arg(arg_subject_type_order_preset())
         .arg(arg_quiet())
         .arg(arg_verbose())
         .arg(arg_version())
+        .arg(arg_watch())
         .arg(arg_src())
+        .subcommand(subcommand_completions())
+        .subcommand(subcommand_man())
+        .subcommand(subcommand_merge_driver())
+        .subcommand(subcommand_diff())
+        .subcommand(subcommand_lsp())
+        .subcommand(subcommand_serve())
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
 }
 
 #[allow(clippy::print_stdout)]
@@ -391,6 +942,26 @@ fn print_version_and_exit(quiet: bool) {
     std::process::exit(0);
 }
 
+fn print_completions_and_exit(shell: Shell) {
+    let mut command = args_matcher();
+    let bin_name = command.get_name().to_owned();
+    clap_complete::generate(shell, &mut command, bin_name, &mut io::stdout());
+    std::process::exit(0);
+}
+
+fn print_man_page_and_exit() -> Result<(), InitError> {
+    clap_mangen::Man::new(args_matcher())
+        .render(&mut io::stdout())
+        .map_err(InitError::ManPage)?;
+    std::process::exit(0);
+}
+
+#[allow(clippy::print_stdout)]
+fn print_merge_driver_git_config_and_exit() {
+    println!("{MERGE_DRIVER_GIT_CONFIG}");
+    std::process::exit(0);
+}
+
 #[derive(Error, Debug)]
 pub enum InitError {
     #[error("Failed to init logging system: {0}")]
@@ -398,42 +969,161 @@ pub enum InitError {
 
     #[error("Failed to change the logging level: {0}")]
     LogChangeLevel(#[from] tracing_subscriber::reload::Error),
+
+    #[error("Failed to write the man page: {0}")]
+    ManPage(#[source] io::Error),
 }
 
-pub fn init() -> Result<(FormatOptions, Vec<PathBuf>), InitError> {
-    let log_reload_handle = logging::setup(crate_name!())?;
-    let args = args_matcher().get_matches();
+/// A subcommand that runs with the resolved configuration,
+/// instead of formatting the sources.
+pub enum SubCommand {
+    /// Merge three versions of a file, as a git merge driver.
+    MergeDriver {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// The path of the file in the repository.
+        path: Option<PathBuf>,
+    },
+    /// Show the triples removed and added between two files.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// The path of the file in the repository,
+        /// if called as a git external diff driver.
+        path: Option<PathBuf>,
+    },
+    /// Run a language server on stdin and stdout.
+    Lsp,
+    /// Serve formatting over HTTP.
+    Serve {
+        listen: String,
+        max_body_size: usize,
+    },
+}
 
-    let quiet = args.get_flag(A_L_QUIET);
-    let version = args.get_flag(A_L_VERSION);
-    if version {
-        print_version_and_exit(quiet);
-    }
+/// What the CLI was asked to do.
+pub struct Settings {
+    /// Figures out the formatting options for each file.
+    pub resolver: Resolver,
+    pub run_options: RunOptions,
+    /// Whether to only print the options in effect for each file.
+    pub print_config: bool,
+    /// Whether to only print the paths of files that need formatting.
+    pub list_different: bool,
+    pub color: ColorChoice,
+    pub report_format: ReportFormat,
+    /// Whether to keep watching the sources for changes.
+    pub watch: bool,
+    /// Whether to format the files staged in git,
+    /// with the sources as pathspecs.
+    pub staged: bool,
+    pub subcommand: Option<SubCommand>,
+    pub src: Vec<PathBuf>,
+}
 
-    let verbose = args.get_flag(A_L_VERBOSE);
-    let log_level = if verbose {
-        LevelFilter::TRACE
-    } else if quiet {
-        LevelFilter::WARN
-    } else {
-        LevelFilter::INFO
-    };
-    logging::set_log_level_tracing(&log_reload_handle, log_level)?;
+fn is_explicit(args: &ArgMatches, id: &str) -> bool {
+    args.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+/// Extracts the sub-command to run, if any,
+/// handling the ones that only print something and exit.
+fn subcommand(args: &ArgMatches) -> Result<Option<SubCommand>, InitError> {
+    Ok(match args.subcommand() {
+        Some((SC_COMPLETIONS, sub_args)) => {
+            if let Some(shell) = sub_args.get_one::<Shell>(A_L_SHELL) {
+                print_completions_and_exit(*shell);
+            }
+            None
+        }
+        Some((SC_MAN, _sub_args)) => {
+            print_man_page_and_exit()?;
+            None
+        }
+        Some((SC_MERGE_DRIVER, sub_args)) => {
+            if sub_args.get_flag(A_L_PRINT_GIT_CONFIG) {
+                print_merge_driver_git_config_and_exit();
+            }
+            let file = |id: &str| sub_args.get_one::<PathBuf>(id).cloned();
+            match (
+                file(A_L_MERGE_BASE),
+                file(A_L_MERGE_OURS),
+                file(A_L_MERGE_THEIRS),
+            ) {
+                (Some(base), Some(ours), Some(theirs)) => Some(SubCommand::MergeDriver {
+                    base,
+                    ours,
+                    theirs,
+                    path: file(A_L_MERGE_PATH),
+                }),
+                _ => None,
+            }
+        }
+        Some((SC_DIFF, sub_args)) => {
+            let files: Vec<PathBuf> = sub_args
+                .get_many::<PathBuf>(A_L_DIFF_FILES)
+                .map(|vals| vals.cloned().collect())
+                .unwrap_or_default();
+            match files.as_slice() {
+                [old, new] => Some(SubCommand::Diff {
+                    old: old.clone(),
+                    new: new.clone(),
+                    path: None,
+                }),
+                // See GIT_EXTERNAL_DIFF_NUM_ARGS and GIT_EXTERNAL_DIFF_RENAME_NUM_ARGS
+                [path, old, _, _, new, _, _] | [_, old, _, _, new, _, _, path, _] => {
+                    Some(SubCommand::Diff {
+                        old: old.clone(),
+                        new: new.clone(),
+                        path: Some(path.clone()),
+                    })
+                }
+                _ => subcommand_diff()
+                    .error(
+                        ErrorKind::WrongNumberOfValues,
+                        formatcp!(
+                            "Expected either 2 files, \
+or the {GIT_EXTERNAL_DIFF_NUM_ARGS} or {GIT_EXTERNAL_DIFF_RENAME_NUM_ARGS} arguments \
+of a git external diff driver"
+                        ),
+                    )
+                    .exit(),
+            }
+        }
+        Some((SC_LSP, _sub_args)) => Some(SubCommand::Lsp),
+        Some((SC_SERVE, sub_args)) => Some(SubCommand::Serve {
+            listen: sub_args
+                .get_one::<String>(A_L_LISTEN)
+                .cloned()
+                .unwrap_or_else(|| prttl::serve::DEFAULT_LISTEN.to_owned()),
+            max_body_size: sub_args
+                .get_one::<usize>(A_L_MAX_BODY_SIZE)
+                .copied()
+                .unwrap_or(prttl::serve::DEFAULT_MAX_BODY_SIZE),
+        }),
+        Some(_) | None => None,
+    })
+}
 
+/// Extracts the formatting options from the arguments:
+/// All of them, and only the explicitly given ones.
+fn format_options(args: &ArgMatches, check: bool) -> (FormatOptions, PartialFormatOptions) {
     let canonicalize = args.get_flag(A_L_CANONICALIZE);
-    let check = args.get_flag(A_L_CHECK);
     let force = args.get_flag(A_L_FORCE);
     let generate_sorting_ids = args.get_flag(A_L_GENERATE_PRTR_SORTING_IDS);
     let prioritize_input_order = args.get_flag(A_L_PRIORITIZE_INPUT_ORDER);
-    let indentation_spaces = args
+    let indentation_num = args
         .get_one::<u8>(A_L_INDENTATION)
         .copied()
-        .unwrap_or(DEFAULT_INDENTATION)
-        .into();
+        .unwrap_or(DEFAULT_INDENTATION);
     let max_nesting = !args.get_flag(A_L_LABEL_ALL_BLANK_NODES);
     let prtr_sorting = !args.get_flag(A_L_NO_PRTR_SORTING);
     let sparql_syntax = !args.get_flag(A_L_NO_SPARQL_SYNTAX);
     let single_leafed_new_lines = args.get_flag(A_L_SINGLE_LEAFED_NEW_LINES);
+    let end_of_line = args
+        .get_one::<EndOfLine>(A_L_END_OF_LINE)
+        .copied()
+        .unwrap_or_default();
     let warn_unsupported_numbers = true;
 
     let predicate_order: Option<Vec<String>> = args
@@ -449,30 +1139,134 @@ pub fn init() -> Result<(FormatOptions, Vec<PathBuf>), InitError> {
         .get_one::<SpecialSubjectTypeOrder>(A_L_SUBJECT_TYPE_ORDER_PRESET)
         .copied();
 
-    let indentation = " ".repeat(indentation_spaces);
+    // These take precedence over the values from configuration files
+    let explicit_options = PartialFormatOptions {
+        indentation: is_explicit(args, A_L_INDENTATION).then_some(indentation_num),
+        single_leafed_new_lines: is_explicit(args, A_L_SINGLE_LEAFED_NEW_LINES)
+            .then_some(single_leafed_new_lines),
+        generate_sorting_ids: is_explicit(args, A_L_GENERATE_PRTR_SORTING_IDS)
+            .then_some(generate_sorting_ids),
+        prioritize_input_order: is_explicit(args, A_L_PRIORITIZE_INPUT_ORDER)
+            .then_some(prioritize_input_order),
+        prtr_sorting: is_explicit(args, A_L_NO_PRTR_SORTING).then_some(prtr_sorting),
+        sparql_syntax: is_explicit(args, A_L_NO_SPARQL_SYNTAX).then_some(sparql_syntax),
+        max_nesting: is_explicit(args, A_L_LABEL_ALL_BLANK_NODES).then_some(max_nesting),
+        canonicalize: is_explicit(args, A_L_CANONICALIZE).then_some(canonicalize),
+        warn_unsupported_numbers: None,
+        subject_type_order_preset,
+        subject_type_order: subject_type_order.clone(),
+        predicate_order_preset,
+        predicate_order: predicate_order.clone(),
+        end_of_line: is_explicit(args, A_L_END_OF_LINE).then_some(end_of_line),
+    };
+
+    let indentation = " ".repeat(indentation_num.into());
+    let base_options = FormatOptions {
+        check,
+        indentation,
+        single_leafed_new_lines,
+        force,
+        generate_sorting_ids,
+        prioritize_input_order,
+        prtr_sorting,
+        sparql_syntax,
+        max_nesting,
+        canonicalize,
+        warn_unsupported_numbers,
+        subject_type_order_preset,
+        subject_type_order,
+        predicate_order_preset,
+        predicate_order,
+        end_of_line,
+    };
+    (base_options, explicit_options)
+}
+
+pub fn init() -> Result<Settings, InitError> {
+    let log_reload_handle = logging::setup(crate_name!())?;
+    let args = args_matcher().get_matches();
+
+    let quiet = args.get_flag(A_L_QUIET);
+    let version = args.get_flag(A_L_VERSION);
+    if version {
+        print_version_and_exit(quiet);
+    }
+
+    let subcommand = subcommand(&args)?;
+
+    let verbose = args.get_flag(A_L_VERBOSE);
+    let log_level = if verbose {
+        LevelFilter::TRACE
+    } else if quiet {
+        LevelFilter::WARN
+    } else {
+        LevelFilter::INFO
+    };
+    logging::set_log_level_tracing(&log_reload_handle, log_level)?;
+
+    let list_different = args.get_flag(A_L_LIST_DIFFERENT);
+    let check = args.get_flag(A_L_CHECK) || list_different;
+    let (base_options, explicit_options) = format_options(&args, check);
+    let color = args
+        .get_one::<ColorChoice>(A_L_COLOR)
+        .copied()
+        .unwrap_or_default();
+    let report_format = args
+        .get_one::<ReportFormat>(A_L_REPORT_FORMAT)
+        .copied()
+        .unwrap_or_default();
+
+    let stdin_filepath = args.get_one::<PathBuf>(A_L_STDIN_FILEPATH).cloned();
+    let output = args.get_one::<PathBuf>(A_L_OUTPUT).cloned();
+    let out_dir = args.get_one::<PathBuf>(A_L_OUT_DIR).cloned();
+    let print_config = args.get_flag(A_L_PRINT_CONFIG);
+    let include: Vec<String> = args
+        .get_many::<String>(A_L_INCLUDE)
+        .map(|vals| vals.cloned().collect())
+        .unwrap_or_default();
+    let exclude: Vec<String> = args
+        .get_many::<String>(A_L_EXCLUDE)
+        .map(|vals| vals.cloned().collect())
+        .unwrap_or_default();
+    let follow_symlinks = args.get_flag(A_L_FOLLOW_SYMLINKS);
+    let convert = args.get_flag(A_L_CONVERT);
+    let watch = args.get_flag(A_L_WATCH);
+    let staged = args.get_flag(A_L_STAGED);
+    let backup_suffix = args.get_one::<String>(A_L_BACKUP).cloned();
+    let cache_dir = (!args.get_flag(A_L_NO_CACHE)).then(|| {
+        args.get_one::<PathBuf>(A_L_CACHE)
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(prttl::cache::DEFAULT_DIR))
+    });
+    let jobs = args
+        .get_one::<u16>(A_L_JOBS)
+        .and_then(|jobs| NonZeroUsize::new((*jobs).into()));
+
     let src: Vec<PathBuf> = args
         .get_many::<PathBuf>(A_L_SRC)
-        .unwrap()
-        .cloned()
-        .collect();
-    Ok((
-        FormatOptions {
-            check,
-            indentation,
-            single_leafed_new_lines,
-            force,
-            generate_sorting_ids,
-            prioritize_input_order,
-            prtr_sorting,
-            sparql_syntax,
-            max_nesting,
-            canonicalize,
-            warn_unsupported_numbers,
-            subject_type_order_preset,
-            subject_type_order,
-            predicate_order_preset,
-            predicate_order,
+        .map(|vals| vals.cloned().collect())
+        .unwrap_or_default();
+    Ok(Settings {
+        resolver: Resolver::new(base_options, explicit_options),
+        run_options: RunOptions {
+            stdin_filepath,
+            output,
+            out_dir,
+            jobs,
+            include,
+            exclude,
+            follow_symlinks,
+            convert,
+            cache_dir,
+            backup_suffix,
         },
+        print_config,
+        list_different,
+        color,
+        report_format,
+        watch,
+        staged,
+        subcommand,
         src,
-    ))
+    })
 }
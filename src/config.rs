@@ -0,0 +1,253 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Loading of [`FormatOptions`] from project configuration files.
+//!
+//! For each file to format,
+//! we look for configuration files named [`FILE_NAME`]
+//! in the directory of the file and all its parent directories,
+//! up to the first one that has `root = true` set.
+//! Configuration files closer to the file take precedence,
+//! and within each configuration file,
+//! matching `[[overrides]]` sections take precedence
+//! over the top-level options.
+//!
+//! A sample configuration file:
+//!
+//! ```toml
+//! root = true
+//! indentation = 4
+//! sparql-syntax = false
+//! predicate-order-preset = "owl"
+//! subject-type-order = ["owl:Ontology", "owl:Class"]
+//!
+//! [[overrides]]
+//! files = ["vocabularies/**/*.ttl"]
+//! predicate-order-preset = "skos"
+//! ```
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::options::{FormatOptions, PartialFormatOptions};
+
+/// The name of the configuration files we look for.
+pub const FILE_NAME: &str = ".prttl.toml";
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Failed to read configuration file '{1}'")]
+    Read(#[source] std::io::Error, PathBuf),
+
+    #[error("Failed to parse configuration file '{1}': {0}")]
+    Parse(#[source] toml::de::Error, PathBuf),
+
+    #[error("Unknown key(s) in configuration file '{1}': {0:?}")]
+    UnknownKeys(Vec<String>, PathBuf),
+
+    #[error("Invalid glob in the overrides of configuration file '{1}': {0}")]
+    Glob(#[source] globset::Error, PathBuf),
+}
+
+/// An `[[overrides]]` section of a configuration file.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct Override {
+    /// Globs matched against the path of a file
+    /// relative to the directory of the configuration file.
+    files: Vec<String>,
+    #[serde(flatten)]
+    options: PartialFormatOptions,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+/// The content of a configuration file.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct ConfigFile {
+    /// Whether to stop looking for configuration files in parent directories.
+    #[serde(default)]
+    root: bool,
+    #[serde(default)]
+    overrides: Vec<Override>,
+    #[serde(flatten)]
+    options: PartialFormatOptions,
+    #[serde(flatten)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+/// A parsed configuration file, ready to be applied.
+struct Config {
+    dir: PathBuf,
+    root: bool,
+    options: PartialFormatOptions,
+    overrides: Vec<(GlobSet, PartialFormatOptions)>,
+}
+
+impl Config {
+    fn load(file: &Path) -> Result<Self, Error> {
+        let content = fs::read_to_string(file).map_err(|err| Error::Read(err, file.to_owned()))?;
+        let config_file: ConfigFile =
+            toml::from_str(&content).map_err(|err| Error::Parse(err, file.to_owned()))?;
+
+        let mut unknown_keys: Vec<String> = config_file.unknown.into_keys().collect();
+        let mut overrides = Vec::new();
+        for ovr in config_file.overrides {
+            unknown_keys.extend(
+                ovr.unknown
+                    .into_keys()
+                    .map(|key| format!("overrides.{key}")),
+            );
+            let mut globs = GlobSetBuilder::new();
+            for pattern in &ovr.files {
+                let glob = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|err| Error::Glob(err, file.to_owned()))?;
+                globs.add(glob);
+            }
+            let globs = globs
+                .build()
+                .map_err(|err| Error::Glob(err, file.to_owned()))?;
+            overrides.push((globs, ovr.options));
+        }
+        if !unknown_keys.is_empty() {
+            return Err(Error::UnknownKeys(unknown_keys, file.to_owned()));
+        }
+
+        Ok(Self {
+            dir: file.parent().map(Path::to_owned).unwrap_or_default(),
+            root: config_file.root,
+            options: config_file.options,
+            overrides,
+        })
+    }
+
+    fn apply_to(&self, file: &Path, options: &mut FormatOptions) {
+        self.options.apply_to(options);
+        let relative = file.strip_prefix(&self.dir).unwrap_or(file);
+        for (globs, ovr_options) in &self.overrides {
+            if globs.is_match(relative) {
+                ovr_options.apply_to(options);
+            }
+        }
+    }
+}
+
+/// Removes `.` and `..` components from a path, without accessing the file-system.
+///
+/// [`std::path::absolute`] keeps `..` components,
+/// which would make us look for configuration files
+/// in the wrong directories, and fail to match override globs.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            Component::Prefix(_) | Component::RootDir | Component::Normal(_) => {
+                normalized.push(component);
+            }
+        }
+    }
+    normalized
+}
+
+/// Figures out the [`FormatOptions`] in effect for each file,
+/// combining base options, configuration files and explicitly set options.
+pub struct Resolver {
+    base: FormatOptions,
+    overrides: PartialFormatOptions,
+    /// Configuration files already loaded, by directory;
+    /// `None` if there is no configuration file in that directory.
+    configs: HashMap<PathBuf, Option<Rc<Config>>>,
+}
+
+impl Resolver {
+    /// Creates a new resolver.
+    ///
+    /// - `base`: The options to use where neither a configuration file
+    ///   nor `overrides` set a value.
+    /// - `overrides`: The options that take precedence over configuration files,
+    ///   usually those explicitly given on the command-line.
+    #[must_use]
+    pub fn new(base: FormatOptions, overrides: PartialFormatOptions) -> Self {
+        Self {
+            base,
+            overrides,
+            configs: HashMap::new(),
+        }
+    }
+
+    /// Forgets the configuration files loaded so far,
+    /// so they get loaded anew when they are needed next,
+    /// picking up any changes made to them in the meantime.
+    pub fn clear_cache(&mut self) {
+        self.configs.clear();
+    }
+
+    /// The configuration files loaded so far.
+    pub fn config_files(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.configs
+            .iter()
+            .filter(|(_dir, config)| config.is_some())
+            .map(|(dir, _config)| dir.join(FILE_NAME))
+    }
+
+    fn config_in(&mut self, dir: &Path) -> Result<Option<Rc<Config>>, Error> {
+        if let Some(config) = self.configs.get(dir) {
+            return Ok(config.as_ref().map(Rc::clone));
+        }
+        let file = dir.join(FILE_NAME);
+        let config = if file.is_file() {
+            tracing::debug!("Loading configuration file '{}' ...", file.display());
+            Some(Rc::new(Config::load(&file)?))
+        } else {
+            None
+        };
+        self.configs
+            .insert(dir.to_owned(), config.as_ref().map(Rc::clone));
+        Ok(config)
+    }
+
+    /// Returns the options in effect for the given file.
+    ///
+    /// The file its self does not need to exist.
+    ///
+    /// # Errors
+    ///
+    /// If one of the relevant configuration files could not be read or parsed.
+    pub fn resolve(&mut self, file: &Path) -> Result<FormatOptions, Error> {
+        let file = normalize(&std::path::absolute(file).unwrap_or_else(|_| file.to_owned()));
+        let mut configs = Vec::new();
+        for dir in file.ancestors().skip(1) {
+            if let Some(config) = self.config_in(dir)? {
+                let root = config.root;
+                configs.push(config);
+                if root {
+                    break;
+                }
+            }
+        }
+
+        let mut options = self.base.clone();
+        for config in configs.iter().rev() {
+            config.apply_to(&file, &mut options);
+        }
+        self.overrides.apply_to(&mut options);
+        Ok(options)
+    }
+}
@@ -0,0 +1,196 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Comparing two versions of a document at the triple level:
+//! Which triples were removed and which were added,
+//! grouped by subject.
+//!
+//! Both graphs are canonicalized,
+//! and blank nodes are matched through their content,
+//! see [`crate::bn_content_labels`].
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+#[cfg(feature = "cli")]
+use std::path::Path;
+use std::sync::Arc;
+
+use oxrdf::vocab::xsd;
+use oxrdf::{LiteralRef, NamedNodeRef, NamedOrBlankNode, TermRef, Triple};
+
+use crate::ast::TNamedNode;
+use crate::bn_content_labels::ContentLabels;
+#[cfg(feature = "cli")]
+use crate::config::Resolver;
+use crate::encoding;
+use crate::error::Error;
+use crate::formatter::escape_local_name;
+use crate::input::Input;
+use crate::options::FormatOptions;
+use crate::parser::{self, InputFormat};
+#[cfg(feature = "cli")]
+use crate::read_file;
+
+/// One of the versions to compare.
+struct Version {
+    input: Input,
+    triples: HashSet<Triple>,
+}
+
+impl Version {
+    fn parse(
+        content: &str,
+        input_format: InputFormat,
+        options: &Arc<FormatOptions>,
+    ) -> Result<Self, Error> {
+        let input = parser::parse_as(
+            encoding::strip_bom(content).as_bytes(),
+            input_format,
+            options,
+        )?;
+        let triples = ContentLabels::new(&input.graph)
+            .triples(&input.graph)
+            .collect();
+        Ok(Self { input, triples })
+    }
+}
+
+/// Renders a named node as in Turtle,
+/// using the prefixes and base of the input.
+fn fmt_named_node(input: &Input, named_node: NamedNodeRef<'_>) -> String {
+    match TNamedNode::from(input, named_node) {
+        TNamedNode::Prefixed(named_node, prefix, local_name) => {
+            if local_name.is_empty() {
+                format!("{prefix}:")
+            } else {
+                escape_local_name(local_name).map_or_else(
+                    || named_node.to_string(),
+                    |local_name| format!("{prefix}:{local_name}"),
+                )
+            }
+        }
+        TNamedNode::Based(_named_node, additional_name) => format!("<{additional_name}>"),
+        TNamedNode::Plain(named_node) => named_node.to_string(),
+    }
+}
+
+/// Renders a term as in Turtle,
+/// using the prefixes and base of the input for named nodes and datatypes.
+fn fmt_term(input: &Input, term: TermRef<'_>) -> String {
+    if let TermRef::NamedNode(named_node) = term {
+        return fmt_named_node(input, named_node);
+    }
+    if let TermRef::Literal(literal) = term {
+        if literal.language().is_none() && literal.datatype() != xsd::STRING {
+            return format!(
+                "{}^^{}",
+                LiteralRef::new_simple_literal(literal.value()),
+                fmt_named_node(input, literal.datatype())
+            );
+        }
+    }
+    term.to_string()
+}
+
+fn fmt_subject(input: &Input, subject: &NamedOrBlankNode) -> String {
+    if let NamedOrBlankNode::NamedNode(named_node) = subject {
+        return fmt_named_node(input, named_node.as_ref());
+    }
+    subject.to_string()
+}
+
+/// Compares two versions of a document at the triple level.
+///
+/// The triples removed from the old and added in the new version
+/// are rendered grouped by subject,
+/// with removed ones prefixed by `-` and added ones by `+`.
+/// Named nodes are written with the prefixes of the version they are from,
+/// subjects with those of the new version.
+/// Returns an empty string if both versions contain the same triples.
+///
+/// # Errors
+///
+/// [`Error::ParseError`] if one of the versions is not valid or not supported
+pub fn diff(
+    old: &str,
+    new: &str,
+    input_format: InputFormat,
+    options: &Arc<FormatOptions>,
+) -> Result<String, Error> {
+    let parse_options = Arc::new(FormatOptions {
+        canonicalize: true,
+        ..(**options).clone()
+    });
+    let old = Version::parse(old, input_format, &parse_options)?;
+    let new = Version::parse(new, input_format, &parse_options)?;
+
+    // predicate, whether it was added, object; per subject
+    let mut subjects: BTreeMap<String, Vec<(String, bool, String)>> = BTreeMap::new();
+    for (version, other, added) in [(&old, &new, false), (&new, &old, true)] {
+        for triple in version.triples.difference(&other.triples) {
+            subjects
+                .entry(fmt_subject(&new.input, &triple.subject))
+                .or_default()
+                .push((
+                    fmt_named_node(&version.input, triple.predicate.as_ref()),
+                    added,
+                    fmt_term(&version.input, triple.object.as_ref()),
+                ));
+        }
+    }
+
+    let mut rendered = String::new();
+    for (subject, mut changes) in subjects {
+        changes.sort_unstable();
+        rendered.push_str(&subject);
+        rendered.push('\n');
+        for (predicate, added, object) in changes {
+            let sign = if added { '+' } else { '-' };
+            writeln!(rendered, "  {sign} {predicate} {object}")?;
+        }
+    }
+    Ok(rendered)
+}
+
+/// Compares two files as [`diff`] does,
+/// with a header naming them,
+/// like a unified diff.
+///
+/// The formatting options and the input format are resolved for `path`,
+/// which defaults to `new`.
+/// If given, it is the path of the file in a git repository,
+/// and `old` and `new` are the (temporary) files git hands to an external diff driver.
+///
+/// # Errors
+///
+/// - [`Error::FailedToReadTargetFile`] and [`Error::InvalidUtf8`] for each version
+/// - any error from [`diff`]
+#[cfg(feature = "cli")]
+pub fn run_diff(
+    resolver: &mut Resolver,
+    old: &Path,
+    new: &Path,
+    path: Option<&Path>,
+) -> Result<String, Error> {
+    let options = Arc::new(FormatOptions {
+        // Comments do not matter when only comparing
+        force: true,
+        ..resolver.resolve(path.unwrap_or(new))?
+    });
+    let input_format = InputFormat::from_path(path.unwrap_or(new)).unwrap_or(InputFormat::Turtle);
+    let changes = diff(&read_file(old)?, &read_file(new)?, input_format, &options)?;
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+    let (old_label, new_label) = path.map_or_else(
+        || (old.display().to_string(), new.display().to_string()),
+        |path| {
+            (
+                format!("a/{}", path.display()),
+                format!("b/{}", path.display()),
+            )
+        },
+    );
+    Ok(format!("--- {old_label}\n+++ {new_label}\n{changes}"))
+}
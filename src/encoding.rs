@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keeping the byte order mark and the line endings of the input,
+//! which the parser and formatter do not care about.
+
+use crate::options::EndOfLine;
+
+/// The UTF-8 byte order mark, as a character.
+const BOM: char = '\u{feff}';
+
+/// Decodes the raw content of a file as UTF-8.
+///
+/// # Errors
+///
+/// The byte offset of the first invalid UTF-8 sequence.
+pub fn decode(bytes: Vec<u8>) -> Result<String, usize> {
+    String::from_utf8(bytes).map_err(|err| err.utf8_error().valid_up_to())
+}
+
+/// The parts of the textual style of a document that get lost on formatting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextStyle {
+    /// Whether the document starts with a byte order mark.
+    pub bom: bool,
+    /// Whether lines end with `\r\n`, instead of just `\n`.
+    pub crlf: bool,
+}
+
+impl TextStyle {
+    /// Detects the style of a document.
+    ///
+    /// The line ending style is taken from the end of the first line.
+    #[must_use]
+    pub fn detect(content: &str) -> Self {
+        Self {
+            bom: content.starts_with(BOM),
+            crlf: content
+                .split_once('\n')
+                .is_some_and(|(first_line, _rest)| first_line.ends_with('\r')),
+        }
+    }
+
+    /// Applies this style to formatted content,
+    /// which uses `\n` line endings and has no byte order mark.
+    ///
+    /// The line endings are chosen according to `end_of_line`.
+    #[must_use]
+    pub fn apply(self, formatted: &str, end_of_line: EndOfLine) -> String {
+        let crlf = match end_of_line {
+            EndOfLine::Auto => self.crlf,
+            EndOfLine::Lf => false,
+            EndOfLine::Crlf => true,
+        };
+        let mut styled = String::with_capacity(formatted.len() + formatted.len() / 16);
+        if self.bom {
+            styled.push(BOM);
+        }
+        if crlf {
+            let mut prev = None;
+            for chr in formatted.chars() {
+                // Line endings within (long) literals might already be `\r\n`
+                if chr == '\n' && prev != Some('\r') {
+                    styled.push('\r');
+                }
+                styled.push(chr);
+                prev = Some(chr);
+            }
+        } else {
+            styled.push_str(formatted);
+        }
+        styled
+    }
+}
+
+/// Removes the byte order mark from the start of the content, if any.
+#[must_use]
+pub fn strip_bom(content: &str) -> &str {
+    content.strip_prefix(BOM).unwrap_or(content)
+}
+
+/// Decodes the `%XX` escapes in a part of a URI,
+/// leaving invalid escapes as they are.
+#[must_use]
+pub fn percent_decode(text: &str) -> String {
+    let mut decoded = Vec::with_capacity(text.len());
+    let mut bytes = text.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let hex: String = bytes.by_ref().take(2).map(char::from).collect();
+            if let Ok(decoded_byte) = u8::from_str_radix(&hex, 16) {
+                decoded.push(decoded_byte);
+                continue;
+            }
+            decoded.push(byte);
+            decoded.extend(hex.bytes());
+        } else {
+            decoded.push(byte);
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
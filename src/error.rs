@@ -2,10 +2,17 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "cli")]
+use crate::config;
 use crate::{bn_sorting_ids, parser};
+use oxttl::TextPosition;
+use std::fmt;
+use std::ops::Range;
 use std::path::PathBuf;
 use thiserror::Error;
 
+/// What failed while listing the files in a directory,
+/// with [`crate::add_files_with_suffix`].
 #[derive(Debug)]
 pub enum FilesListErrorType {
     ReadDir,
@@ -13,7 +20,43 @@ pub enum FilesListErrorType {
     EvaluateFileType,
 }
 
+/// The steps of writing formatted content to a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteStep {
+    /// Creating the temporary file next to the target file.
+    CreateTemporary,
+    /// Writing the content to the temporary file.
+    Write,
+    /// Copying the permissions of the target file to the temporary file.
+    CopyPermissions,
+    /// Copying the previous content of the target file to the backup file.
+    Backup,
+    /// Renaming the temporary file to the target file.
+    Rename,
+}
+
+impl fmt::Display for WriteStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CreateTemporary => "creating a temporary file",
+            Self::Write => "writing to a temporary file",
+            Self::CopyPermissions => "copying permissions",
+            Self::Backup => "creating a backup",
+            Self::Rename => "replacing the file",
+        })
+    }
+}
+
+/// An error from a library we only use for some features,
+/// boxed so the shape of [`Error`] does not depend on the enabled features.
+pub type ForeignError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Everything that can go wrong.
+///
+/// New variants may be added in minor releases,
+/// and some of them only ever occur with the `cli` feature enabled.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     #[error("Input is not equivalent to the (re-)formatted version of its self: {0}")]
     Check(String),
@@ -36,20 +79,164 @@ Consider refactoring the input first."
     #[error("The target file to format does not seem to exist: '{0}'")]
     TargetFileDoesNotExist(PathBuf),
 
-    #[error("Error while reading file: '{0}'")]
-    FailedToReadTargetFile(PathBuf),
+    #[error("Error while reading file: '{1}'")]
+    FailedToReadTargetFile(#[source] std::io::Error, PathBuf),
+
+    #[error("'{0}' is not valid UTF-8, starting at byte offset {1}")]
+    InvalidUtf8(PathBuf, usize),
 
     #[error("Failed to parse input as turtle: {0}")]
     ParseError(#[from] parser::Error),
 
-    #[error("Error while writing to file: '{0}'")]
-    FailedToWriteFormattedFile(#[source] std::io::Error, PathBuf),
+    /// Wraps a `config::Error`, only available with the `cli` feature.
+    #[error(transparent)]
+    Config(ForeignError),
+
+    #[error("Error while writing to file '{1}', when {2}")]
+    FailedToWriteFormattedFile(#[source] std::io::Error, PathBuf, WriteStep),
 
     #[error("Failed to list files in input directory '{0}': {1:?}")]
     FailedToListFilesInInputDir(#[source] std::io::Error, PathBuf, FilesListErrorType),
 
+    #[error("Failed to list files in input directory '{1}': {0}")]
+    FailedToWalkInputDir(#[source] ForeignError, PathBuf),
+
+    #[error("Can not tell the RDF serialization of the file from its extension: '{0}'")]
+    UnknownInputFormat(PathBuf),
+
+    #[error("Invalid include or exclude glob: {0}")]
+    InvalidGlob(#[source] ForeignError),
+
     #[error("Failed to create Turtle file tree structure: {0}")]
     FailedToCreateTurtleStructure(String),
+
+    #[error("Error while reading from stdin")]
+    FailedToReadStdin(#[source] std::io::Error),
+
+    #[error("Error while writing to stdout")]
+    FailedToWriteStdout(#[source] std::io::Error),
+
+    #[error("'{0}': {1}")]
+    File(PathBuf, #[source] Box<Self>),
+
+    #[error("The output file already exists; use --force to overwrite it: '{0}'")]
+    OutputFileExists(PathBuf),
+
+    #[error("A single output file can only be used with a single input file, but got {0} inputs")]
+    OutputForMultipleInputs(usize),
+
+    #[error("Failed to create output directory: '{1}'")]
+    FailedToCreateOutputDir(#[source] std::io::Error, PathBuf),
+
+    #[error("Failed to watch for changes: {0}")]
+    FailedToWatch(#[source] ForeignError),
+
+    #[error("Failed to run '{1}'")]
+    FailedToRunGit(#[source] std::io::Error, String),
+
+    #[error("'{0}' failed: {1}")]
+    GitCommandFailed(String, String),
+
+    #[error("Invalid language server message: {0}")]
+    InvalidLspMessage(String),
+
+    #[error("Failed to listen on '{1}'")]
+    FailedToListen(#[source] std::io::Error, String),
+}
+
+impl Error {
+    /// A short, stable, machine-readable identifier for the kind of this error.
+    ///
+    /// For [`Self::ParseError`] and [`Self::File`],
+    /// this is the kind of the wrapped error.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Check(_) => "check",
+            Self::MultipleBases => "multiple-bases",
+            Self::TurtleSyntaxError(_) => "turtle-syntax",
+            Self::Format(_) => "format",
+            Self::SortingIdGeneration(_) => "sorting-id-generation",
+            Self::TargetFileDoesNotExist(_) => "target-file-does-not-exist",
+            Self::FailedToReadTargetFile(_, _) => "failed-to-read-target-file",
+            Self::InvalidUtf8(_, _) => "invalid-utf8",
+            Self::ParseError(err) => err.kind(),
+            Self::Config(_) => "config",
+            Self::FailedToWriteFormattedFile(_, _, _) => "failed-to-write-formatted-file",
+            Self::FailedToListFilesInInputDir(_, _, _) => "failed-to-list-files-in-input-dir",
+            Self::FailedToWalkInputDir(_, _) => "failed-to-walk-input-dir",
+            Self::UnknownInputFormat(_) => "unknown-input-format",
+            Self::InvalidGlob(_) => "invalid-glob",
+            Self::FailedToCreateTurtleStructure(_) => "failed-to-create-turtle-structure",
+            Self::FailedToReadStdin(_) => "failed-to-read-stdin",
+            Self::FailedToWriteStdout(_) => "failed-to-write-stdout",
+            Self::File(_, err) => err.kind(),
+            Self::OutputFileExists(_) => "output-file-exists",
+            Self::OutputForMultipleInputs(_) => "output-for-multiple-inputs",
+            Self::FailedToCreateOutputDir(_, _) => "failed-to-create-output-dir",
+            Self::FailedToWatch(_) => "failed-to-watch",
+            Self::FailedToRunGit(_, _) => "failed-to-run-git",
+            Self::GitCommandFailed(_, _) => "git-command-failed",
+            Self::InvalidLspMessage(_) => "invalid-lsp-message",
+            Self::FailedToListen(_, _) => "failed-to-listen",
+        }
+    }
+
+    /// Where in the input this error occurred, if known.
+    /// Lines and columns are 0-based.
+    #[must_use]
+    pub fn location(&self) -> Option<Range<TextPosition>> {
+        match self {
+            Self::TurtleSyntaxError(err) => Some(err.location()),
+            Self::ParseError(err) => err.location(),
+            Self::File(_, err) => err.location(),
+            Self::Check(_)
+            | Self::MultipleBases
+            | Self::Format(_)
+            | Self::SortingIdGeneration(_)
+            | Self::TargetFileDoesNotExist(_)
+            | Self::FailedToReadTargetFile(_, _)
+            | Self::InvalidUtf8(_, _)
+            | Self::Config(_)
+            | Self::FailedToWriteFormattedFile(_, _, _)
+            | Self::FailedToListFilesInInputDir(_, _, _)
+            | Self::UnknownInputFormat(_)
+            | Self::FailedToCreateTurtleStructure(_)
+            | Self::FailedToReadStdin(_)
+            | Self::FailedToWriteStdout(_)
+            | Self::OutputFileExists(_)
+            | Self::OutputForMultipleInputs(_)
+            | Self::FailedToCreateOutputDir(_, _)
+            | Self::FailedToRunGit(_, _)
+            | Self::GitCommandFailed(_, _)
+            | Self::InvalidLspMessage(_)
+            | Self::FailedToListen(_, _) => None,
+            Self::FailedToWalkInputDir(_, _) | Self::InvalidGlob(_) | Self::FailedToWatch(_) => {
+                None
+            }
+        }
+    }
+
+    /// Where in the input this error occurred, if known,
+    /// with 1-based lines and columns, as usually shown to humans.
+    /// The byte offsets stay 0-based.
+    #[must_use]
+    pub fn location_1based(&self) -> Option<Range<TextPosition>> {
+        let to_1based = |position: TextPosition| TextPosition {
+            line: position.line + 1,
+            column: position.column + 1,
+            offset: position.offset,
+        };
+        self.location()
+            .map(|range| to_1based(range.start)..to_1based(range.end))
+    }
+}
+
+#[cfg(feature = "cli")]
+impl From<config::Error> for Error {
+    fn from(err: config::Error) -> Self {
+        Self::Config(Box::new(err))
+    }
 }
 
 pub type FmtResult<T> = std::result::Result<T, Error>;
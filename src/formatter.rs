@@ -14,16 +14,17 @@ use crate::error::FmtResult;
 use crate::options::FormatOptions;
 use oxiri::IriParseError;
 use oxrdf::{BlankNodeRef, NamedNodeRef, vocab::rdf, vocab::xsd};
-use oxrdf::{NamedNode, TripleRef};
+use oxrdf::{NamedNode, NamedOrBlankNode, NamedOrBlankNodeRef, TripleRef};
 use regex::Regex;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::fmt::{self, Write};
 use std::rc::Rc;
+use std::sync::Arc;
 use std::sync::LazyLock;
 
 use crate::bn_sorting_ids::Cache as BNSortingIdsCache;
-use crate::input::Input;
+use crate::input::{DatasetInput, Input};
 
 /// The regex to match a DOUBLE from the Turtle grammar,
 /// which is *not* equivalent with xsd:double!
@@ -37,7 +38,7 @@ static RE_TURTLE_DOUBLE: LazyLock<Regex> =
 /// Fails only if an I/O error occurs while writing to an in-memory buffer.
 /// This is basically only possible when the buffer is full,
 /// and no more memory can be allocated.
-pub fn format(input: &Input, options: Rc<FormatOptions>) -> FmtResult<String> {
+pub fn format(input: &Input, options: Arc<FormatOptions>) -> FmtResult<String> {
     let mut output = String::new();
     let mut context = Context {
         indent_level: 0,
@@ -50,16 +51,45 @@ pub fn format(input: &Input, options: Rc<FormatOptions>) -> FmtResult<String> {
     Ok(output)
 }
 
+/// Does the actual formatting/pretty-printing of a TriG document.
+///
+/// Base and prefixes come first,
+/// followed by the default graph
+/// and the named graphs, sorted by name.
+/// Each graph is formatted like a Turtle document.
+///
+/// # Errors
+///
+/// The same as [`format`].
+pub fn format_dataset(dataset: &DatasetInput, options: Arc<FormatOptions>) -> FmtResult<String> {
+    let mut output = String::new();
+    let mut context = Context {
+        indent_level: 0,
+        output: &mut output,
+    };
+    let mut formatter = TurtleFormatter::new(&dataset.default_graph, options);
+    formatter.construct_tree()?;
+    tracing::debug!("{:#?}", formatter.tree);
+    formatter.fmt_doc(&mut context)?;
+    for (graph_name, input) in &dataset.named_graphs {
+        let mut graph_formatter = TurtleFormatter::new(input, Arc::<_>::clone(&formatter.options));
+        graph_formatter.construct_tree()?;
+        tracing::debug!("{:#?}", graph_formatter.tree);
+        graph_formatter.fmt_graph(&mut context, graph_name)?;
+    }
+    Ok(output)
+}
+
 struct TurtleFormatter<'graph> {
     input: &'graph Input,
-    options: Rc<FormatOptions>,
+    options: Arc<FormatOptions>,
     unreferenced_blank_nodes: HashSet<BlankNodeRef<'graph>>,
     col_involved_triples: Rc<RefCell<Vec<TripleRef<'graph>>>>,
     tree: TRoot<'graph>,
 }
 
 impl<'graph> TurtleFormatter<'graph> {
-    fn new(input: &'graph Input, options: Rc<FormatOptions>) -> Self {
+    fn new(input: &'graph Input, options: Arc<FormatOptions>) -> Self {
         Self {
             input,
             options,
@@ -88,16 +118,18 @@ impl<'graph> TurtleFormatter<'graph> {
             &self.col_involved_triples,
             self.input,
         )
-        .map_err(|err| Error::FailedToCreateTurtleStructure(err.to_string()))
-        .unwrap();
+        .map_err(|err| Error::FailedToCreateTurtleStructure(err.to_string()))?;
 
-        let mut bn_sorting_ids = BNSortingIdsCache::new(self.input, Rc::<_>::clone(&self.options));
+        let mut bn_sorting_ids = BNSortingIdsCache::new(self.input, Arc::<_>::clone(&self.options));
+        if self.options.prtr_sorting || self.options.generate_sorting_ids {
+            bn_sorting_ids.check_unique()?;
+        }
         if self.options.generate_sorting_ids {
             bn_sorting_ids.ensure_sorting_ids_assigned(&self.col_involved_triples.borrow())?;
         }
 
         let context = SortingContext {
-            options: Rc::<_>::clone(&self.options),
+            options: Arc::<_>::clone(&self.options),
             graph: &self.input.graph,
             bn_sorting_ids: Rc::new(RefCell::new(bn_sorting_ids)),
             bn_objects_input_order: self
@@ -143,7 +175,7 @@ impl<'graph> TurtleFormatter<'graph> {
     }
 }
 
-fn escape_local_name(value: &str) -> Option<String> {
+pub(crate) fn escape_local_name(value: &str) -> Option<String> {
     // TODO: PLX
     // [168s] 	PN_LOCAL 	::= 	(PN_CHARS_U | ':' | [0-9] | PLX) ((PN_CHARS | '.' | ':' | PLX)* (PN_CHARS | ':' | PLX))?
     let mut output = String::with_capacity(value.len());
@@ -676,6 +708,31 @@ so we write them as data-typed literals."
         Ok(())
     }
 
+    fn fmt_graph<W: Write>(
+        &self,
+        context: &mut Context<W>,
+        graph_name: &'graph NamedOrBlankNode,
+    ) -> FmtResult<()> {
+        writeln!(context.output)?;
+        if self.options.sparql_syntax {
+            write!(context.output, "GRAPH ")?;
+        }
+        match graph_name.as_ref() {
+            NamedOrBlankNodeRef::NamedNode(named_node_ref) => {
+                self.fmt_named_node(context, &TNamedNode::from(self.input, named_node_ref))?;
+            }
+            NamedOrBlankNodeRef::BlankNode(blank_node_ref) => {
+                write!(context.output, "{blank_node_ref}")?;
+            }
+        }
+        writeln!(context.output, " {{")?;
+        context.indent_level += 1;
+        self.fmt_triples(context)?;
+        context.indent_level -= 1;
+        writeln!(context.output, "}}")?;
+        Ok(())
+    }
+
     fn fmt_doc<W: Write>(&self, context: &mut Context<W>) -> FmtResult<()> {
         self.fmt_base(context)?;
 
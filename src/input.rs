@@ -4,6 +4,8 @@
 
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 use oxrdf::BlankNode;
 use oxrdf::Graph;
@@ -21,6 +23,31 @@ pub struct Input {
     // Blank node objects in the order they (first) appear in the input
     pub bn_objects_input_order: Vec<BlankNode>,
     pub graph: Graph,
+    // Only set if this is one of the graphs of a dataset
+    pub dataset: Option<Arc<DatasetShared>>,
+}
+
+/// What the graphs of a dataset share.
+///
+/// Blank nodes are scoped to the whole dataset,
+/// so the same blank node may be used in multiple graphs.
+pub struct DatasetShared {
+    /// All the `prtr:sortingId` triples of all the graphs.
+    pub sorting_ids: Graph,
+    /// The blank nodes used in more than one graph, or as a graph name.
+    /// These always keep their label,
+    /// as nesting them would lose the connection.
+    pub blank_nodes: HashSet<BlankNode>,
+}
+
+/// The content of a TriG document:
+/// Multiple graphs, sharing base and prefixes.
+pub struct DatasetInput {
+    /// The default graph,
+    /// with the base and prefixes of the whole document.
+    pub default_graph: Input,
+    /// The named graphs, sorted by name.
+    pub named_graphs: Vec<(NamedOrBlankNode, Input)>,
 }
 
 impl Input {
@@ -2,27 +2,71 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::error::{Error, FilesListErrorType};
-use crate::{formatter::format, options::FormatOptions};
-use diffy::{PatchFormatter, create_patch};
-use std::ffi::OsStr;
-use std::fs;
-use std::path::{Path, PathBuf};
-use std::rc::Rc;
+#[cfg(feature = "cli")]
+use crate::cache::Cache;
+#[cfg(feature = "cli")]
+use crate::config::Resolver;
+use crate::encoding::TextStyle;
+use crate::error::Error;
+#[cfg(feature = "cli")]
+use crate::error::FilesListErrorType;
+use crate::formatter::{format, format_dataset};
+use crate::options::FormatOptions;
+#[cfg(feature = "cli")]
+use crate::options::RunOptions;
+use crate::parser::InputFormat;
+#[cfg(feature = "cli")]
+use crate::report::{FileReport, Report, Status};
+#[cfg(feature = "cli")]
+use diffy::create_patch;
+use std::sync::Arc;
+#[cfg(feature = "cli")]
+use std::{
+    ffi::OsStr,
+    fs,
+    io::{self, Read, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
 
+#[cfg(feature = "cli")]
 use git_version::git_version;
 
 pub mod ast;
+pub mod bn_content_labels;
 pub mod bn_sorting_ids;
+#[cfg(feature = "cli")]
+pub mod cache;
 pub mod compare;
+#[cfg(feature = "cli")]
+pub mod config;
 pub mod constants;
 pub mod context;
+pub mod diff;
+pub mod encoding;
 pub mod error;
 pub mod formatter;
 pub mod input;
+#[cfg(feature = "cli")]
+pub mod lsp;
+pub mod merge;
 pub mod options;
 pub mod parser;
+#[cfg(feature = "cli")]
+pub mod report;
+#[cfg(feature = "cli")]
+pub mod serve;
+#[cfg(feature = "cli")]
+pub mod staged;
 pub mod vocab;
+#[cfg(feature = "cli")]
+pub mod walk;
+#[cfg(feature = "cli")]
+pub mod watch;
+#[cfg(feature = "cli")]
+pub mod write;
 
 // This tests rust code in the README with doc-tests.
 // Though, It will not appear in the generated documentation.
@@ -30,46 +74,298 @@ pub mod vocab;
 #[cfg(doctest)]
 pub struct ReadmeDoctests;
 
+#[cfg(feature = "cli")]
 pub const VERSION: &str = git_version!(cargo_prefix = "", fallback = "unknown");
+/// The version of this crate,
+/// as the version from git is not available without the `cli` feature.
+#[cfg(not(feature = "cli"))]
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Runs the formatter on the given files.
+/// The source to specify for reading from stdin and writing to stdout.
+pub const STDIN_SOURCE: &str = "-";
+
+/// Turns formatting off for a file
+/// when found at the start of a comment on its first line,
+/// like in `# prttl: skip - generated from schema.json`.
+///
+/// Meant for generated files and files copied from elsewhere,
+/// which sit in the directories to format.
+pub const SKIP_MARKER: &str = "prttl: skip";
+
+/// Whether formatting is turned off for the given content,
+/// with [`SKIP_MARKER`].
+#[must_use]
+pub fn is_skipped(content: &str) -> bool {
+    encoding::strip_bom(content)
+        .lines()
+        .next()
+        .and_then(|line| line.trim_start().strip_prefix('#'))
+        .and_then(|comment| comment.trim_start().strip_prefix(SKIP_MARKER))
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+}
+
+/// Parses and formats a single (supposedly) Turtle document.
 ///
 /// # Errors
 ///
-/// Any error from [`Error`].
-pub fn run(options: &Rc<FormatOptions>, input_files: &Vec<PathBuf>) -> Result<(), Error> {
-    for file in input_files {
-        let original =
-            fs::read_to_string(file).map_err(|_err| Error::FailedToReadTargetFile(file.clone()))?;
-        let input = parser::parse(original.as_bytes(), options)?;
-        let formatted = format(&input, Rc::<_>::clone(options))?;
-        if original == formatted {
-            // Nothing to do
-            continue;
-        }
-        if options.check {
-            let patch = create_patch(&original, &formatted);
-            let formatted_patch = PatchFormatter::new()
-                .with_color()
-                .fmt_patch(&patch)
-                .to_string();
-            return Err(Error::Check(formatted_patch));
+/// - [`Error::ParseError`] if the content is not valid or not supported Turtle
+/// - any other error from [`format`]
+pub fn format_str(original: &str, options: &Arc<FormatOptions>) -> Result<String, Error> {
+    format_str_as(original, InputFormat::Turtle, options)
+}
+
+/// Parses a single document of the given format,
+/// and formats it as Turtle,
+/// or as TriG if it is TriG already.
+///
+/// # Errors
+///
+/// - [`Error::ParseError`] if the content is not valid or not supported
+/// - any other error from [`format`]
+pub fn format_str_as(
+    original: &str,
+    input_format: InputFormat,
+    options: &Arc<FormatOptions>,
+) -> Result<String, Error> {
+    if input_format == InputFormat::TriG {
+        let dataset = parser::parse_trig(original.as_bytes(), options)?;
+        return format_dataset(&dataset, Arc::<_>::clone(options));
+    }
+    let input = parser::parse_as(original.as_bytes(), input_format, options)?;
+    format(&input, Arc::<_>::clone(options))
+}
+
+/// Formats a single document like [`format_str_as`],
+/// but keeps its byte order mark,
+/// and - according to [`FormatOptions::end_of_line`] - its line endings.
+///
+/// # Errors
+///
+/// The same as [`format_str_as`].
+pub fn format_str_styled(
+    original: &str,
+    input_format: InputFormat,
+    options: &Arc<FormatOptions>,
+) -> Result<String, Error> {
+    let formatted = format_str_as(encoding::strip_bom(original), input_format, options)?;
+    Ok(TextStyle::detect(original).apply(&formatted, options.end_of_line))
+}
+
+/// Reads a file and decodes it as UTF-8.
+///
+/// # Errors
+///
+/// - [`Error::FailedToReadTargetFile`]
+/// - [`Error::InvalidUtf8`]
+#[cfg(feature = "cli")]
+fn read_file(file: &Path) -> Result<String, Error> {
+    let bytes =
+        fs::read(file).map_err(|err| Error::FailedToReadTargetFile(err, file.to_path_buf()))?;
+    encoding::decode(bytes).map_err(|offset| Error::InvalidUtf8(file.to_path_buf(), offset))
+}
+
+/// The format to read the given file as.
+///
+/// This is TriG for files with a TriG extension and Turtle for all others,
+/// unless we [convert](RunOptions::convert).
+///
+/// # Errors
+///
+/// [`Error::UnknownInputFormat`]
+#[cfg(feature = "cli")]
+fn input_format(file: &Path, run_options: &RunOptions) -> Result<InputFormat, Error> {
+    if run_options.convert {
+        InputFormat::from_path(file).ok_or_else(|| Error::UnknownInputFormat(file.to_path_buf()))
+    } else {
+        Ok(InputFormat::from_path(file)
+            .filter(|format| *format == InputFormat::TriG)
+            .unwrap_or(InputFormat::Turtle))
+    }
+}
+
+/// Compares original and formatted content,
+/// as done in check mode.
+#[cfg(feature = "cli")]
+fn check_status(original: &str, formatted: &str) -> Status {
+    if original == formatted {
+        Status::Unchanged
+    } else {
+        Status::NeedsFormatting(create_patch(original, formatted).to_string())
+    }
+}
+
+/// Runs the formatter on content read from stdin,
+/// and writes the result to stdout.
+///
+/// In check mode, nothing is written to stdout;
+/// the result is only reported through the returned value.
+///
+/// # Errors
+///
+/// [`Error::FailedToReadStdin`];
+/// all other errors - including content that is not valid UTF-8 -
+/// are reported as [`Status::Error`].
+#[cfg(feature = "cli")]
+pub fn run_stdin(resolver: &mut Resolver, run_options: &RunOptions) -> Result<Report, Error> {
+    let path = run_options
+        .stdin_filepath
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(STDIN_SOURCE));
+    let mut bytes = Vec::new();
+    io::stdin()
+        .read_to_end(&mut bytes)
+        .map_err(Error::FailedToReadStdin)?;
+    let status = encoding::decode(bytes)
+        .map_err(|offset| Error::InvalidUtf8(path.clone(), offset))
+        .and_then(|original| process_stdin(resolver, run_options, &path, &original))
+        .unwrap_or_else(Status::Error);
+    Ok(Report {
+        files: vec![FileReport { path, status }],
+    })
+}
+
+#[cfg(feature = "cli")]
+fn process_stdin(
+    resolver: &mut Resolver,
+    run_options: &RunOptions,
+    path: &Path,
+    original: &str,
+) -> Result<Status, Error> {
+    let options = &Arc::new(resolver.resolve(path)?);
+    let skipped = is_skipped(original);
+    let formatted = if skipped {
+        tracing::debug!(
+            "Passing '{}' through, as formatting is turned off for it",
+            path.display()
+        );
+        original.to_owned()
+    } else {
+        format_str_styled(original, input_format(path, run_options)?, options)?
+    };
+    if options.check {
+        return Ok(if skipped {
+            Status::Skipped
+        } else {
+            check_status(original, &formatted)
+        });
+    }
+    if let Some(output) = &run_options.output {
+        write_output(options, run_options, output, &formatted)?;
+    } else {
+        io::stdout()
+            .write_all(formatted.as_bytes())
+            .map_err(Error::FailedToWriteStdout)?;
+    }
+    Ok(if skipped {
+        Status::Skipped
+    } else if original == formatted && !run_options.convert {
+        Status::Unchanged
+    } else {
+        Status::Formatted
+    })
+}
+
+/// A file to be formatted.
+#[cfg(feature = "cli")]
+pub struct InputFile {
+    /// Where to read the file from.
+    pub path: PathBuf,
+    /// The path of the file relative to the source it was found through.
+    ///
+    /// This is used to mirror the directory structure
+    /// when writing to [`RunOptions::out_dir`].
+    /// For a file given directly as a source, this is just its file name.
+    pub relative: PathBuf,
+}
+
+#[cfg(feature = "cli")]
+impl InputFile {
+    /// Where to write the formatted content of this file to,
+    /// if not back to the file its self.
+    ///
+    /// When we [convert](RunOptions::convert),
+    /// this is always set,
+    /// and by default a Turtle file next to the source.
+    #[must_use]
+    pub fn output_path(&self, run_options: &RunOptions) -> Option<PathBuf> {
+        let relative = if run_options.convert {
+            self.relative.with_extension(parser::TURTLE_EXTENSION)
+        } else {
+            self.relative.clone()
+        };
+        run_options
+            .output
+            .clone()
+            .or_else(|| {
+                run_options
+                    .out_dir
+                    .as_ref()
+                    .map(|out_dir| out_dir.join(relative))
+            })
+            .or_else(|| {
+                run_options
+                    .convert
+                    .then(|| self.path.with_extension(parser::TURTLE_EXTENSION))
+            })
+    }
+}
+
+/// Collects the files to format from the given sources,
+/// which may be files or directories.
+///
+/// Directories are searched recursively for files with one of the given extensions,
+/// as described in [`walk::find_files`].
+/// Files given directly are always included.
+///
+/// # Errors
+///
+/// - [`Error::TargetFileDoesNotExist`] if a source is neither a file nor a directory
+/// - any error from [`walk::find_files`]
+#[cfg(feature = "cli")]
+pub fn collect_input_files(
+    src: &[PathBuf],
+    extensions: &[&str],
+    run_options: &RunOptions,
+) -> Result<Vec<InputFile>, Error> {
+    let mut input_files = Vec::new();
+    for source in src {
+        if source.is_file() {
+            input_files.push(InputFile {
+                path: source.clone(),
+                relative: source.file_name().map(PathBuf::from).unwrap_or_default(),
+            });
+        } else if source.is_dir() {
+            for file in walk::find_files(source, extensions, run_options)? {
+                let relative = file.strip_prefix(source).unwrap_or(&file).to_path_buf();
+                input_files.push(InputFile {
+                    path: file,
+                    relative,
+                });
+            }
+        } else {
+            return Err(Error::TargetFileDoesNotExist(source.clone()));
         }
-        fs::write(file, formatted)
-            .map_err(|err| Error::FailedToWriteFormattedFile(err, file.clone()))?;
     }
-    Ok(())
+    Ok(input_files)
 }
 
 /// Recursively adds files from a directory,
 /// which have the given suffix,
 /// to a list of files given as parameter.
 ///
+/// Unlike [`collect_input_files`], this does not respect ignore files,
+/// nor does it skip hidden files.
+///
 /// # Errors
 ///
 /// - if the directory does not exist
 /// - if the directory is not a directory
 /// - if the directory is not readable (an issue with file-system permissions)
+#[cfg(feature = "cli")]
+#[deprecated(
+    since = "0.4.0",
+    note = "use `collect_input_files` or `walk::find_files` instead"
+)]
 pub fn add_files_with_suffix(
     dir: &Path,
     extension: &OsStr,
@@ -98,8 +394,187 @@ pub fn add_files_with_suffix(
                 files.push(file);
             }
         } else if entry_type.is_dir() {
+            #[allow(deprecated)]
             add_files_with_suffix(&entry.path(), extension, files)?;
         }
     }
     Ok(())
 }
+
+/// Writes formatted content to a file other than the one it was read from.
+///
+/// # Errors
+///
+/// - [`Error::OutputFileExists`] if the file exists and we are not forced to overwrite
+/// - [`Error::FailedToCreateOutputDir`]
+/// - any error from [`write::write_file`]
+#[cfg(feature = "cli")]
+fn write_output(
+    options: &FormatOptions,
+    run_options: &RunOptions,
+    output: &Path,
+    formatted: &str,
+) -> Result<(), Error> {
+    if !options.force && output.exists() {
+        return Err(Error::OutputFileExists(output.to_path_buf()));
+    }
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| Error::FailedToCreateOutputDir(err, parent.to_path_buf()))?;
+    }
+    write::write_file(output, formatted, run_options.backup_suffix.as_deref())
+}
+
+/// Runs the formatter on the given files,
+/// each with the options the `resolver` finds for it.
+///
+/// The formatted content is written back to each file,
+/// unless [`RunOptions::output`] or [`RunOptions::out_dir`] is set.
+/// A failure to process one file does not stop the processing of the others;
+/// it is reported as [`Status::Error`] instead.
+///
+/// Files are processed concurrently (see [`RunOptions::jobs`]),
+/// but the report always lists them in the order they were given.
+///
+/// Files that are formatted in place
+/// and are known to the cache (see [`RunOptions::cache_dir`])
+/// to be formatted already are skipped.
+/// So are files starting with [`SKIP_MARKER`],
+/// which are reported as [`Status::Skipped`].
+///
+/// # Errors
+///
+/// [`Error::OutputForMultipleInputs`]
+#[cfg(feature = "cli")]
+pub fn run(
+    resolver: &mut Resolver,
+    run_options: &RunOptions,
+    input_files: &[InputFile],
+) -> Result<Report, Error> {
+    if run_options.output.is_some() && input_files.len() > 1 {
+        return Err(Error::OutputForMultipleInputs(input_files.len()));
+    }
+
+    // The resolver caches the configuration files it loads,
+    // so we resolve the options for all files up front.
+    let mut statuses = Vec::with_capacity(input_files.len());
+    let mut tasks = Vec::with_capacity(input_files.len());
+    for (idx, input_file) in input_files.iter().enumerate() {
+        match resolver.resolve(&input_file.path) {
+            Ok(options) => tasks.push((idx, input_file, Arc::new(options))),
+            Err(err) => statuses.push((idx, Status::Error(err.into()))),
+        }
+    }
+
+    let jobs = run_options
+        .jobs
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get)
+        .min(tasks.len());
+    let cache = run_options.cache_dir.clone().map(Cache::new);
+    statuses.extend(process_in_parallel(
+        &tasks,
+        run_options,
+        cache.as_ref(),
+        jobs,
+    ));
+    statuses.sort_unstable_by_key(|(idx, _status)| *idx);
+
+    let files = statuses
+        .into_iter()
+        .filter_map(|(idx, status)| {
+            input_files.get(idx).map(|input_file| FileReport {
+                path: input_file.path.clone(),
+                status,
+            })
+        })
+        .collect();
+    Ok(Report { files })
+}
+
+/// Processes the given files on `jobs` threads,
+/// returning the status of each of them together with its index.
+#[cfg(feature = "cli")]
+fn process_in_parallel(
+    tasks: &[(usize, &InputFile, Arc<FormatOptions>)],
+    run_options: &RunOptions,
+    cache: Option<&Cache>,
+    jobs: usize,
+) -> Vec<(usize, Status)> {
+    let next_task = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut statuses = Vec::new();
+                    while let Some((idx, input_file, options)) =
+                        tasks.get(next_task.fetch_add(1, Ordering::Relaxed))
+                    {
+                        let status = process_file(options, run_options, cache, input_file)
+                            .unwrap_or_else(Status::Error);
+                        statuses.push((*idx, status));
+                    }
+                    statuses
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+#[cfg(feature = "cli")]
+fn process_file(
+    options: &Arc<FormatOptions>,
+    run_options: &RunOptions,
+    cache: Option<&Cache>,
+    input_file: &InputFile,
+) -> Result<Status, Error> {
+    let file = &input_file.path;
+    let original = read_file(file)?;
+    if is_skipped(&original) {
+        tracing::debug!(
+            "Skipping '{}', as formatting is turned off for it",
+            file.display()
+        );
+        // Written elsewhere, the file is copied as it is
+        if let Some(output) = input_file
+            .output_path(run_options)
+            .filter(|_| !options.check)
+        {
+            write_output(options, run_options, &output, &original)?;
+        }
+        return Ok(Status::Skipped);
+    }
+    let input_format = input_format(file, run_options)?;
+    // The cache only knows whether content is formatted,
+    // so it is of no use if the result has to be written elsewhere.
+    let cache = cache.filter(|_| input_file.output_path(run_options).is_none());
+    if cache.is_some_and(|cache| cache.is_formatted(&original, input_format, options)) {
+        tracing::debug!("Skipping '{}', as it is formatted already", file.display());
+        return Ok(Status::Unchanged);
+    }
+    let formatted = format_str_styled(&original, input_format, options)?;
+    if let Some(cache) = cache.filter(|_| original == formatted) {
+        cache.insert(&original, input_format, options);
+    }
+    if options.check {
+        return Ok(check_status(&original, &formatted));
+    }
+    if let Some(output) = input_file.output_path(run_options) {
+        write_output(options, run_options, &output, &formatted)?;
+    } else if original != formatted {
+        write::write_file(file, &formatted, run_options.backup_suffix.as_deref())?;
+    }
+    Ok(if original == formatted && !run_options.convert {
+        Status::Unchanged
+    } else {
+        Status::Formatted
+    })
+}
@@ -0,0 +1,538 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! A [Language Server Protocol](https://microsoft.github.io/language-server-protocol/)
+//! server, for formatting and error reporting from within editors.
+//!
+//! It supports full document synchronization,
+//! formatting of whole documents as a minimal set of line edits,
+//! and publishes the errors found when formatting as diagnostics.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use diffy::Line;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::config::Resolver;
+use crate::error::Error;
+use crate::options::RunOptions;
+use crate::parser;
+use crate::{VERSION, encoding, format_str_styled, input_format, is_skipped};
+
+const HEADER_CONTENT_LENGTH: &str = "Content-Length";
+
+/// The JSON-RPC error code for a message that is not valid JSON.
+const PARSE_ERROR: i32 = -32700;
+/// The JSON-RPC error code for valid JSON that is no valid message.
+const INVALID_REQUEST: i32 = -32600;
+/// The JSON-RPC error code for an unknown method.
+const METHOD_NOT_FOUND: i32 = -32601;
+/// The JSON-RPC error code for invalid parameters.
+const INVALID_PARAMS: i32 = -32602;
+
+/// The LSP `TextDocumentSyncKind` for sending the full content on each change.
+const SYNC_FULL: u8 = 1;
+/// The LSP `DiagnosticSeverity` for errors.
+const SEVERITY_ERROR: u8 = 1;
+
+#[derive(Deserialize)]
+struct Message {
+    id: Option<Value>,
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct TextDocumentIdentifier {
+    uri: String,
+}
+
+#[derive(Deserialize)]
+struct TextDocumentItem {
+    uri: String,
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidOpenParams {
+    text_document: TextDocumentItem,
+}
+
+#[derive(Deserialize)]
+struct ContentChange {
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidChangeParams {
+    text_document: TextDocumentIdentifier,
+    content_changes: Vec<ContentChange>,
+}
+
+/// The parameters of all requests and notifications
+/// that only refer to a document.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentParams {
+    text_document: TextDocumentIdentifier,
+}
+
+/// A position in a document,
+/// with the character counted in UTF-16 code units.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct TextRange {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub new_text: String,
+}
+
+#[derive(Serialize)]
+struct Diagnostic {
+    range: TextRange,
+    severity: u8,
+    code: &'static str,
+    source: &'static str,
+    message: String,
+}
+
+fn to_u32(value: usize) -> u32 {
+    u32::try_from(value).unwrap_or(u32::MAX)
+}
+
+/// The position of a byte offset in the text.
+fn position(text: &str, offset: usize) -> Position {
+    let before = text.get(..offset).unwrap_or(text);
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Position {
+        line: to_u32(before.matches('\n').count()),
+        character: to_u32(
+            before
+                .get(line_start..)
+                .unwrap_or_default()
+                .encode_utf16()
+                .count(),
+        ),
+    }
+}
+
+/// The position of the start of a line,
+/// or of the end of the text, if it has less lines.
+fn line_start(text: &str, line: usize) -> Position {
+    let offset = if line == 0 {
+        Some(0)
+    } else {
+        text.match_indices('\n')
+            .nth(line - 1)
+            .map(|(index, _newline)| index + 1)
+    };
+    position(text, offset.unwrap_or(text.len()))
+}
+
+/// The edits that turn `original` into `formatted`,
+/// each replacing a run of whole lines.
+///
+/// Only the lines that differ are touched,
+/// so the cursor of the editor stays where it is in most cases.
+#[must_use]
+pub fn text_edits(original: &str, formatted: &str) -> Vec<TextEdit> {
+    let edit = |start: usize, deleted: usize, new_text: String| TextEdit {
+        range: TextRange {
+            start: line_start(original, start),
+            end: line_start(original, start + deleted),
+        },
+        new_text,
+    };
+    let mut edits = Vec::new();
+    let patch = diffy::create_patch(original, formatted);
+    for hunk in patch.hunks() {
+        let old_range = hunk.old_range();
+        // Line numbers in patches are 1-based,
+        // except for the line an insertion into an empty range follows
+        let mut line = if old_range.is_empty() {
+            old_range.start()
+        } else {
+            old_range.start().saturating_sub(1)
+        };
+        // The first line, the number of lines deleted and the inserted text
+        let mut pending: Option<(usize, usize, String)> = None;
+        for hunk_line in hunk.lines() {
+            match hunk_line {
+                Line::Context(_) => {
+                    if let Some((start, deleted, new_text)) = pending.take() {
+                        edits.push(edit(start, deleted, new_text));
+                    }
+                    line += 1;
+                }
+                Line::Delete(_) => {
+                    pending.get_or_insert_with(|| (line, 0, String::new())).1 += 1;
+                    line += 1;
+                }
+                Line::Insert(inserted) => pending
+                    .get_or_insert_with(|| (line, 0, String::new()))
+                    .2
+                    .push_str(inserted),
+            }
+        }
+        if let Some((start, deleted, new_text)) = pending.take() {
+            edits.push(edit(start, deleted, new_text));
+        }
+    }
+    edits
+}
+
+/// The byte ranges of the base or prefix declarations in the text.
+fn declarations(text: &str, keyword: &str, prefix: Option<&str>) -> Vec<Range<usize>> {
+    let name = prefix.map_or_else(
+        || "<".to_owned(),
+        |prefix| format!("{}:", regex::escape(prefix)),
+    );
+    // Without unicode support, as that is not enabled for the regex crate
+    Regex::new(&format!(r"(?i-u)(?:@{keyword}|\b{keyword})\s+{name}"))
+        .map(|regex| regex.find_iter(text).map(|found| found.range()).collect())
+        .unwrap_or_default()
+}
+
+/// Finds where in the text an error without a location was caused.
+fn locate(err: &parser::Error, text: &str) -> Option<Range<usize>> {
+    match err {
+        parser::Error::PrefixRedefinition(prefix) => {
+            declarations(text, "prefix", Some(prefix)).pop()
+        }
+        parser::Error::PrefixAndBaseShareNamespace(prefix) => {
+            declarations(text, "prefix", Some(prefix))
+                .into_iter()
+                .next()
+        }
+        parser::Error::MultiplePrefixesForNamespace(duplicates) => duplicates
+            .values()
+            .flatten()
+            .filter_map(|prefix| {
+                declarations(text, "prefix", Some(prefix))
+                    .into_iter()
+                    .next()
+            })
+            .max_by_key(|range| range.start),
+        parser::Error::BaseRedefinition => declarations(text, "base", None).into_iter().nth(1),
+        parser::Error::Comment => parser::find_comment(text),
+        parser::Error::Canonicalization(_)
+        | parser::Error::TurtleSyntaxError(_)
+        | parser::Error::BaseIri(_)
+        | parser::Error::RdfXmlSyntaxError(_)
+        | parser::Error::JsonLdSyntaxError(_)
+        | parser::Error::NamedGraph => None,
+    }
+}
+
+/// The range of the text an error refers to,
+/// or the start of the text, if unknown.
+fn error_range(err: &Error, text: &str) -> TextRange {
+    let content = encoding::strip_bom(text);
+    let bom_len = text.len() - content.len();
+    let offset = |offset: u64| usize::try_from(offset).unwrap_or(usize::MAX);
+    let byte_range = err
+        .location()
+        .map(|location| offset(location.start.offset)..offset(location.end.offset))
+        .or_else(|| {
+            if let Error::ParseError(parse_err) = err {
+                locate(parse_err, content)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(0..0);
+    TextRange {
+        start: position(text, byte_range.start.saturating_add(bom_len)),
+        end: position(text, byte_range.end.saturating_add(bom_len)),
+    }
+}
+
+/// The path of a file URI,
+/// or the URI itself for other schemes.
+///
+/// # Errors
+///
+/// [`Error::InvalidLspMessage`] if the file URI names a host other than `localhost`,
+/// as we can only access local files.
+fn uri_to_path(uri: &str) -> Result<PathBuf, Error> {
+    let Some(authority_and_path) = uri.strip_prefix("file://") else {
+        return Ok(PathBuf::from(uri));
+    };
+    let (host, path) = authority_and_path
+        .find('/')
+        .map_or((authority_and_path, ""), |index| {
+            authority_and_path.split_at(index)
+        });
+    if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+        return Err(Error::InvalidLspMessage(format!(
+            "Only local files are supported, but the URI names the host '{host}': '{uri}'"
+        )));
+    }
+    // "file:///C:/..." on Windows
+    let path = if cfg!(windows) {
+        path.trim_start_matches('/')
+    } else {
+        path
+    };
+    Ok(PathBuf::from(encoding::percent_decode(path)))
+}
+
+/// Formats a document with the options resolved for its path,
+/// the same way the CLI does,
+/// which leaves it as it is if formatting is turned off for it.
+fn format_document(resolver: &mut Resolver, uri: &str, text: &str) -> Result<String, Error> {
+    if is_skipped(text) {
+        return Ok(text.to_owned());
+    }
+    let path = uri_to_path(uri)?;
+    let options = Arc::new(resolver.resolve(&path)?);
+    format_str_styled(text, input_format(&path, &RunOptions::default())?, &options)
+}
+
+/// Reads the content of the next message,
+/// or returns `None` at the end of the input.
+fn read_message(input: &mut impl BufRead) -> Result<Option<Vec<u8>>, Error> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if input
+            .read_line(&mut line)
+            .map_err(Error::FailedToReadStdin)?
+            == 0
+        {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case(HEADER_CONTENT_LENGTH) {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let content_length = content_length.ok_or_else(|| {
+        Error::InvalidLspMessage(format!("Missing '{HEADER_CONTENT_LENGTH}' header"))
+    })?;
+    let mut content = vec![0; content_length];
+    input
+        .read_exact(&mut content)
+        .map_err(Error::FailedToReadStdin)?;
+    Ok(Some(content))
+}
+
+/// Parses the content of a message,
+/// returning the JSON-RPC error code and message if that fails.
+fn parse_message(content: &[u8]) -> Result<Message, (i32, String)> {
+    let value: Value =
+        serde_json::from_slice(content).map_err(|err| (PARSE_ERROR, err.to_string()))?;
+    Message::deserialize(value).map_err(|err| (INVALID_REQUEST, err.to_string()))
+}
+
+struct Server<'a, W: Write> {
+    resolver: &'a mut Resolver,
+    output: W,
+    /// The content of the open documents, by URI.
+    documents: HashMap<String, String>,
+}
+
+impl<W: Write> Server<'_, W> {
+    fn send(&mut self, message: &Value) -> Result<(), Error> {
+        let content = message.to_string();
+        write!(
+            self.output,
+            "{HEADER_CONTENT_LENGTH}: {}\r\n\r\n{content}",
+            content.len()
+        )
+        .and_then(|()| self.output.flush())
+        .map_err(Error::FailedToWriteStdout)
+    }
+
+    fn respond(&mut self, id: &Value, result: &Value) -> Result<(), Error> {
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+    }
+
+    fn respond_error(&mut self, id: &Value, code: i32, message: &str) -> Result<(), Error> {
+        self.send(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }))
+    }
+
+    fn notify(&mut self, method: &str, params: &Value) -> Result<(), Error> {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> Result<(), Error> {
+        let diagnostics: Vec<Diagnostic> = self
+            .documents
+            .get(uri)
+            .and_then(|text| {
+                format_document(self.resolver, uri, text)
+                    .err()
+                    .map(|err| Diagnostic {
+                        range: error_range(&err, text),
+                        severity: SEVERITY_ERROR,
+                        code: err.kind(),
+                        source: env!("CARGO_PKG_NAME"),
+                        message: err.to_string(),
+                    })
+            })
+            .into_iter()
+            .collect();
+        self.notify(
+            "textDocument/publishDiagnostics",
+            &json!({ "uri": uri, "diagnostics": diagnostics }),
+        )
+    }
+
+    /// Handles a request,
+    /// returning its result,
+    /// or `None` if the method is not supported.
+    fn handle_request(&mut self, method: &str, params: Value) -> Option<Result<Value, String>> {
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": SYNC_FULL,
+                    "documentFormattingProvider": true,
+                },
+                "serverInfo": { "name": env!("CARGO_PKG_NAME"), "version": VERSION },
+            })),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/formatting" => serde_json::from_value::<DocumentParams>(params)
+                .map(|params| {
+                    let uri = params.text_document.uri;
+                    self.documents
+                        .get(&uri)
+                        .and_then(|text| {
+                            // Errors are published as diagnostics already
+                            format_document(self.resolver, &uri, text)
+                                .ok()
+                                .map(|formatted| text_edits(text, &formatted))
+                        })
+                        .map_or(Value::Null, |edits| json!(edits))
+                })
+                .map_err(|err| err.to_string()),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    fn handle_notification(&mut self, method: &str, params: Value) -> Result<(), Error> {
+        match method {
+            "textDocument/didOpen" => {
+                if let Ok(params) = serde_json::from_value::<DidOpenParams>(params) {
+                    let document = params.text_document;
+                    self.documents.insert(document.uri.clone(), document.text);
+                    self.publish_diagnostics(&document.uri)?;
+                }
+            }
+            "textDocument/didChange" => {
+                if let Ok(params) = serde_json::from_value::<DidChangeParams>(params) {
+                    if let Some(change) = params.content_changes.into_iter().last() {
+                        let uri = params.text_document.uri;
+                        self.documents.insert(uri.clone(), change.text);
+                        self.publish_diagnostics(&uri)?;
+                    }
+                }
+            }
+            "textDocument/didClose" => {
+                if let Ok(params) = serde_json::from_value::<DocumentParams>(params) {
+                    let uri = params.text_document.uri;
+                    self.documents.remove(&uri);
+                    // Clears the diagnostics
+                    self.publish_diagnostics(&uri)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// Runs the language server,
+/// reading messages from `input` and writing to `output`,
+/// until the client sends the `exit` notification,
+/// or closes the input.
+///
+/// # Errors
+///
+/// - [`Error::FailedToReadStdin`] and [`Error::FailedToWriteStdout`]
+///   if reading or writing a message fails
+/// - [`Error::InvalidLspMessage`] if the header of a message can not be parsed;
+///   a content that can not be parsed is answered with an error instead
+pub fn serve(
+    resolver: &mut Resolver,
+    mut input: impl BufRead,
+    output: impl Write,
+) -> Result<(), Error> {
+    let mut server = Server {
+        resolver,
+        output,
+        documents: HashMap::new(),
+    };
+    while let Some(content) = read_message(&mut input)? {
+        let message = match parse_message(&content) {
+            Ok(message) => message,
+            Err((code, err)) => {
+                // Without a message, there is no ID to respond to
+                server.respond_error(&Value::Null, code, &err)?;
+                continue;
+            }
+        };
+        let Some(method) = message.method else {
+            // A response to a request of ours; we send none
+            continue;
+        };
+        if method == "exit" {
+            break;
+        }
+        if let Some(id) = message.id {
+            match server.handle_request(&method, message.params) {
+                Some(Ok(result)) => server.respond(&id, &result)?,
+                Some(Err(err)) => server.respond_error(&id, INVALID_PARAMS, &err)?,
+                None => server.respond_error(
+                    &id,
+                    METHOD_NOT_FOUND,
+                    &format!("Unsupported method: {method}"),
+                )?,
+            }
+        } else {
+            server.handle_notification(&method, message.params)?;
+        }
+    }
+    Ok(())
+}
+
+/// Runs the language server on stdin and stdout,
+/// as [`serve`] does.
+///
+/// # Errors
+///
+/// The same as [`serve`].
+pub fn run(resolver: &mut Resolver) -> Result<(), Error> {
+    serve(resolver, io::stdin().lock(), io::stdout().lock())
+}
@@ -3,9 +3,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use cli::InitError;
+use prttl::config::Resolver;
 use prttl::error::Error;
-use std::ffi::OsStr;
-use std::rc::Rc;
+use prttl::options::PartialFormatOptions;
+use prttl::parser::InputFormat;
+use prttl::report::{ColorChoice, Report, ReportFormat, Status, Summary};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use thiserror::Error as ThisError;
 
 mod cli;
@@ -17,6 +21,27 @@ pub enum CliError {
 
     #[error("Failed to run the formatter: {0}")]
     Format(#[from] Error),
+
+    #[error("Failed to serialize the configuration: {0}")]
+    SerializeConfig(#[from] toml::ser::Error),
+
+    #[error(
+        "Reading from stdin ('{}') can not be combined with other sources",
+        prttl::STDIN_SOURCE
+    )]
+    StdinWithOtherSources,
+
+    #[error(
+        "Reading from stdin ('{}') can not be combined with watching",
+        prttl::STDIN_SOURCE
+    )]
+    WatchStdin,
+
+    #[error("{0}")]
+    Unsuccessful(Summary),
+
+    #[error("The merge left {0} conflict(s), marked at the end of the file")]
+    MergeConflicts(usize),
 }
 
 impl std::fmt::Debug for CliError {
@@ -25,21 +50,176 @@ impl std::fmt::Debug for CliError {
     }
 }
 
+#[allow(clippy::print_stdout)]
+fn print_config<'a>(
+    resolver: &mut Resolver,
+    files: impl Iterator<Item = &'a Path>,
+) -> Result<(), CliError> {
+    for file in files {
+        let options = resolver.resolve(file).map_err(Error::from)?;
+        let options_toml = toml::to_string(&PartialFormatOptions::from(&options))?;
+        println!("# {}\n{options_toml}", file.display());
+    }
+    Ok(())
+}
+
+/// Logs the errors in the report, and prints the rest of it,
+/// to stdout or - if that is taken by formatted content - to stderr.
+fn print_report(
+    report: &Report,
+    report_format: ReportFormat,
+    list_different: bool,
+    color: ColorChoice,
+    to_stderr: bool,
+) -> Result<(), Error> {
+    for file_report in &report.files {
+        if let Status::Error(err) = &file_report.status {
+            tracing::error!("'{}': {err}", file_report.path.display());
+        }
+    }
+    let (mut out, use_color): (Box<dyn Write>, bool) = if to_stderr {
+        let err = io::stderr();
+        let use_color = color.use_color(&err);
+        (Box::new(err.lock()), use_color)
+    } else {
+        let out = io::stdout();
+        let use_color = color.use_color(&out);
+        (Box::new(out.lock()), use_color)
+    };
+    match report_format {
+        ReportFormat::Human => report.write_human(&mut out, list_different, use_color),
+        ReportFormat::Json => report.write_json(&mut out),
+        ReportFormat::Sarif => report.write_sarif(&mut out),
+    }
+    .map_err(Error::FailedToWriteStdout)
+}
+
+/// Prints the report and turns it into the result of the program.
+fn finish(
+    report: &Report,
+    report_format: ReportFormat,
+    list_different: bool,
+    color: ColorChoice,
+    to_stderr: bool,
+) -> Result<(), CliError> {
+    print_report(report, report_format, list_different, color, to_stderr)?;
+    if report.is_success() {
+        let summary = report.summary();
+        if summary.skipped > 0 {
+            tracing::info!("{summary}");
+        }
+        Ok(())
+    } else {
+        Err(CliError::Unsuccessful(report.summary()))
+    }
+}
+
+/// Runs one of the sub-commands, which do not format files.
+fn run_subcommand(subcommand: cli::SubCommand, resolver: &mut Resolver) -> Result<(), CliError> {
+    match subcommand {
+        cli::SubCommand::MergeDriver {
+            base,
+            ours,
+            theirs,
+            path,
+        } => {
+            let conflicts =
+                prttl::merge::run_merge_driver(resolver, &base, &ours, &theirs, path.as_deref())?;
+            for conflict in &conflicts {
+                tracing::warn!("Conflict: {conflict}");
+            }
+            if conflicts.is_empty() {
+                Ok(())
+            } else {
+                Err(CliError::MergeConflicts(conflicts.len()))
+            }
+        }
+        cli::SubCommand::Diff { old, new, path } => {
+            let changes = prttl::diff::run_diff(resolver, &old, &new, path.as_deref())?;
+            io::stdout()
+                .write_all(changes.as_bytes())
+                .map_err(Error::FailedToWriteStdout)?;
+            Ok(())
+        }
+        cli::SubCommand::Lsp => Ok(prttl::lsp::run(resolver)?),
+        cli::SubCommand::Serve {
+            listen,
+            max_body_size,
+        } => Ok(prttl::serve::run(resolver, &listen, max_body_size)?),
+    }
+}
+
 fn main() -> Result<(), CliError> {
-    let (options, src) = cli::init()?;
-    let options = Rc::new(options);
-
-    let mut files = Vec::new();
-    for source in src {
-        if source.is_file() {
-            files.push(source);
-        } else if source.is_dir() {
-            prttl::add_files_with_suffix(&source, OsStr::new("ttl"), &mut files)?;
-        } else {
-            return Err(Error::TargetFileDoesNotExist(source).into());
+    let cli::Settings {
+        mut resolver,
+        run_options,
+        print_config: only_print_config,
+        list_different,
+        color,
+        report_format,
+        watch,
+        staged,
+        subcommand,
+        src,
+    } = cli::init()?;
+
+    if let Some(subcommand) = subcommand {
+        return run_subcommand(subcommand, &mut resolver);
+    }
+
+    if staged {
+        let report = prttl::staged::run(&mut resolver, &run_options, &src)?;
+        return finish(&report, report_format, list_different, color, false);
+    }
+
+    if src
+        .iter()
+        .any(|source| source.as_os_str() == prttl::STDIN_SOURCE)
+    {
+        if src.len() > 1 {
+            return Err(CliError::StdinWithOtherSources);
+        }
+        if watch {
+            return Err(CliError::WatchStdin);
         }
+        if only_print_config {
+            let stdin_filepath = run_options
+                .stdin_filepath
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(prttl::STDIN_SOURCE));
+            return print_config(&mut resolver, std::iter::once(stdin_filepath.as_path()));
+        }
+        let report = prttl::run_stdin(&mut resolver, &run_options)?;
+        // Unless written to a file, stdout is taken by the formatted content
+        let to_stderr = run_options.output.is_none();
+        return finish(&report, report_format, list_different, color, to_stderr);
     }
 
-    prttl::run(&options, &files)?;
-    Ok(())
+    let input_formats = if run_options.convert {
+        InputFormat::CONVERTIBLE.as_slice()
+    } else {
+        InputFormat::FORMATTABLE.as_slice()
+    };
+    let extensions: Vec<&str> = input_formats
+        .iter()
+        .flat_map(|format| format.extensions())
+        .copied()
+        .collect();
+    if watch {
+        prttl::watch::watch(&mut resolver, &run_options, &src, &extensions, |report| {
+            if let Err(err) = print_report(report, report_format, list_different, color, false) {
+                tracing::error!("{err}");
+            }
+            if !report.is_success() {
+                tracing::warn!("{}", report.summary());
+            }
+        })?;
+        return Ok(());
+    }
+    let files = prttl::collect_input_files(&src, &extensions, &run_options)?;
+    if only_print_config {
+        return print_config(&mut resolver, files.iter().map(|file| file.path.as_path()));
+    }
+    let report = prttl::run(&mut resolver, &run_options, &files)?;
+    finish(&report, report_format, list_different, color, false)
 }
@@ -0,0 +1,349 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Merging three versions of a Turtle document at the triple level,
+//! as done by a git merge driver.
+//!
+//! Textual merges of formatted Turtle often fail,
+//! because sorting moves whole subject blocks around.
+//! Here, the objects of each subject and predicate are merged instead.
+//!
+//! Blank nodes are matched through their content,
+//! see [`crate::bn_content_labels`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Write as _};
+#[cfg(feature = "cli")]
+use std::path::Path;
+use std::sync::Arc;
+
+use oxrdf::{NamedNode, NamedOrBlankNode, Term, Triple};
+
+use crate::bn_content_labels::ContentLabels;
+#[cfg(feature = "cli")]
+use crate::config::Resolver;
+use crate::encoding;
+#[cfg(feature = "cli")]
+use crate::encoding::TextStyle;
+use crate::error::Error;
+use crate::formatter::format;
+use crate::options::FormatOptions;
+use crate::parser;
+#[cfg(feature = "cli")]
+use crate::{read_file, write};
+
+type Objects = HashSet<Term>;
+
+/// The objects of each predicate of each subject.
+type Statements = HashMap<NamedOrBlankNode, HashMap<NamedNode, Objects>>;
+
+/// One of the versions to merge.
+struct Version {
+    base: Option<String>,
+    prefixes: BTreeMap<String, String>,
+    subjects_in_order: Vec<NamedOrBlankNode>,
+    statements: Statements,
+}
+
+impl Version {
+    fn parse(content: &str, options: &Arc<FormatOptions>) -> Result<Self, Error> {
+        let input = parser::parse(encoding::strip_bom(content).as_bytes(), options)?;
+        let labels = ContentLabels::new(&input.graph);
+
+        let mut statements: Statements = HashMap::new();
+        for triple in labels.triples(&input.graph) {
+            statements
+                .entry(triple.subject)
+                .or_default()
+                .entry(triple.predicate)
+                .or_default()
+                .insert(triple.object);
+        }
+        Ok(Self {
+            subjects_in_order: input
+                .subjects_in_order
+                .iter()
+                .map(|subject| labels.subject(subject.as_ref()))
+                .collect(),
+            base: input.base,
+            prefixes: input.prefixes,
+            statements,
+        })
+    }
+}
+
+/// A subject and predicate whose objects were changed differently
+/// in both versions, with both removing some of the original ones.
+///
+/// The merged result contains none of its objects,
+/// but a git-style conflict block listing those of all versions.
+pub struct Conflict {
+    pub subject: NamedOrBlankNode,
+    pub predicate: NamedNode,
+    pub base: Vec<Term>,
+    pub ours: Vec<Term>,
+    pub theirs: Vec<Term>,
+}
+
+/// The size of the conflict markers, as used by git by default.
+const MARKER_SIZE: usize = 7;
+
+fn sorted(objects: &Objects) -> Vec<Term> {
+    let mut sorted: Vec<Term> = objects.iter().cloned().collect();
+    sorted.sort_by_cached_key(ToString::to_string);
+    sorted
+}
+
+impl Conflict {
+    fn new(
+        subject: &NamedOrBlankNode,
+        predicate: &NamedNode,
+        base: &Objects,
+        ours: &Objects,
+        theirs: &Objects,
+    ) -> Self {
+        Self {
+            subject: subject.clone(),
+            predicate: predicate.clone(),
+            base: sorted(base),
+            ours: sorted(ours),
+            theirs: sorted(theirs),
+        }
+    }
+
+    /// Writes the statements of all three versions,
+    /// surrounded by git-style (diff3) conflict markers.
+    fn write_markers(&self, out: &mut String) -> fmt::Result {
+        for (marker, side, objects) in [
+            ("<", " ours", &self.ours),
+            ("|", " base", &self.base),
+            ("=", "", &self.theirs),
+        ] {
+            writeln!(out, "{}{side}", marker.repeat(MARKER_SIZE))?;
+            for object in objects {
+                writeln!(out, "{} {} {object} .", self.subject, self.predicate)?;
+            }
+        }
+        writeln!(out, "{} theirs", ">".repeat(MARKER_SIZE))
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.subject, self.predicate)?;
+        for (side, objects) in [
+            ("base", &self.base),
+            ("ours", &self.ours),
+            ("theirs", &self.theirs),
+        ] {
+            write!(f, "\n  {:<8}", format!("{side}:"))?;
+            if objects.is_empty() {
+                write!(f, "(none)")?;
+            }
+            for (index, object) in objects.iter().enumerate() {
+                if index > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{object}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Merges the objects of a single subject and predicate.
+///
+/// Additions and removals of either version are applied,
+/// unless both versions removed some of the original objects
+/// and at least one of them added new ones,
+/// for example when changing a value on one side
+/// and removing it on the other.
+/// That is a conflict, for which `None` is returned.
+fn merge_objects(base: &Objects, ours: &Objects, theirs: &Objects) -> Option<Objects> {
+    if ours == theirs || theirs == base {
+        return Some(ours.clone());
+    }
+    if ours == base {
+        return Some(theirs.clone());
+    }
+    let changes = |side: &Objects| {
+        (
+            base.difference(side).next().is_some(),
+            side.difference(base).next().is_some(),
+        )
+    };
+    let (ours_removed, ours_added) = changes(ours);
+    let (theirs_removed, theirs_added) = changes(theirs);
+    if ours_removed && theirs_removed && (ours_added || theirs_added) {
+        return None;
+    }
+    Some(
+        base.iter()
+            .filter(|object| ours.contains(*object) && theirs.contains(*object))
+            .chain(ours.difference(base))
+            .chain(theirs.difference(base))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Merges the prefixes,
+/// preferring ours where both versions changed the same prefix.
+///
+/// A prefix added by theirs is skipped
+/// if its namespace has a prefix already.
+fn merge_prefixes(
+    base: &BTreeMap<String, String>,
+    ours: &BTreeMap<String, String>,
+    theirs: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut merged = ours.clone();
+    for prefix in base.keys() {
+        if !theirs.contains_key(prefix) && base.get(prefix) == ours.get(prefix) {
+            merged.remove(prefix);
+        }
+    }
+    for (prefix, namespace) in theirs {
+        let changed_by_theirs = base.get(prefix) != Some(namespace);
+        let changed_by_ours = base.get(prefix) != ours.get(prefix);
+        if changed_by_theirs
+            && !changed_by_ours
+            && !merged
+                .values()
+                .any(|merged_namespace| merged_namespace == namespace)
+        {
+            merged.insert(prefix.clone(), namespace.clone());
+        }
+    }
+    merged
+}
+
+/// The result of a merge.
+pub struct Merged {
+    /// The formatted, merged document.
+    pub content: String,
+    /// If not empty, `content` ends with a conflict block for each of these,
+    /// and is thus no longer valid Turtle.
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Merges two versions of a Turtle document - ours and theirs -
+/// that both derive from a common base version,
+/// at the triple level,
+/// and formats the result.
+///
+/// # Errors
+///
+/// - [`Error::ParseError`] if one of the versions is not valid or not supported Turtle
+/// - any other error from [`format`]
+/// - [`Error::Format`] if writing the conflict markers failed
+pub fn merge(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    options: &Arc<FormatOptions>,
+) -> Result<Merged, Error> {
+    let parse_options = Arc::new(FormatOptions {
+        canonicalize: true,
+        ..(**options).clone()
+    });
+    let base = Version::parse(base, &parse_options)?;
+    let ours = Version::parse(ours, &parse_options)?;
+    let theirs = Version::parse(theirs, &parse_options)?;
+
+    let no_predicates = HashMap::new();
+    let no_objects = HashSet::new();
+    let mut conflicts = Vec::new();
+    let mut triples = Vec::new();
+    let mut seen_subjects = HashSet::new();
+    for subject in ours
+        .subjects_in_order
+        .iter()
+        .chain(&theirs.subjects_in_order)
+    {
+        if !seen_subjects.insert(subject) {
+            continue;
+        }
+        let [base_predicates, ours_predicates, theirs_predicates] = [&base, &ours, &theirs]
+            .map(|version| version.statements.get(subject).unwrap_or(&no_predicates));
+        let mut predicates: Vec<&NamedNode> = ours_predicates
+            .keys()
+            .chain(theirs_predicates.keys())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        predicates.sort_unstable_by_key(|predicate| predicate.as_str());
+        for predicate in predicates {
+            let [base_objects, ours_objects, theirs_objects] =
+                [base_predicates, ours_predicates, theirs_predicates]
+                    .map(|predicates| predicates.get(predicate).unwrap_or(&no_objects));
+            let Some(objects) = merge_objects(base_objects, ours_objects, theirs_objects) else {
+                conflicts.push(Conflict::new(
+                    subject,
+                    predicate,
+                    base_objects,
+                    ours_objects,
+                    theirs_objects,
+                ));
+                continue;
+            };
+            triples.extend(
+                sorted(&objects)
+                    .into_iter()
+                    .map(|object| Triple::new(subject.clone(), predicate.clone(), object)),
+            );
+        }
+    }
+
+    let merged_base = if ours.base == base.base {
+        theirs.base
+    } else {
+        ours.base
+    };
+    let prefixes = merge_prefixes(&base.prefixes, &ours.prefixes, &theirs.prefixes);
+    let input = parser::input_from_parts(
+        triples.iter().map(Triple::as_ref),
+        merged_base.as_deref(),
+        &prefixes,
+        options,
+    )?;
+    let mut content = format(&input, Arc::<_>::clone(options))?;
+    for conflict in &conflicts {
+        content.push('\n');
+        conflict.write_markers(&mut content)?;
+    }
+    Ok(Merged { content, conflicts })
+}
+
+/// Merges three versions of a Turtle file,
+/// as a git merge driver does:
+/// `base` is the common ancestor of `ours` and `theirs`,
+/// and the result is written to `ours`.
+///
+/// The formatting options are resolved for `path`,
+/// the path of the file in the repository;
+/// they default to those of `ours`.
+///
+/// # Errors
+///
+/// - [`Error::FailedToReadTargetFile`] and [`Error::InvalidUtf8`] for each version
+/// - any error from [`merge`]
+/// - [`Error::FailedToWriteFormattedFile`]
+#[cfg(feature = "cli")]
+pub fn run_merge_driver(
+    resolver: &mut Resolver,
+    base: &Path,
+    ours: &Path,
+    theirs: &Path,
+    path: Option<&Path>,
+) -> Result<Vec<Conflict>, Error> {
+    let options = Arc::new(resolver.resolve(path.unwrap_or(ours))?);
+    let base_content = read_file(base)?;
+    let ours_content = read_file(ours)?;
+    let theirs_content = read_file(theirs)?;
+    let merged = merge(&base_content, &ours_content, &theirs_content, &options)?;
+    let content = TextStyle::detect(&ours_content).apply(&merged.content, options.end_of_line);
+    write::write_file(ours, &content, None)?;
+    Ok(merged.conflicts)
+}
@@ -2,9 +2,16 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::LazyLock;
 
+#[cfg(feature = "cli")]
 use clap::ValueEnum;
+use serde::de::IntoDeserializer;
+use serde::de::value::Error as NameError;
+use serde::{Deserialize, Serialize};
 
 static CLS_ORDER_OWL: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
     vec![
@@ -329,7 +336,15 @@ static PRED_ORDER_RDF: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
     ]
 });
 
-#[derive(ValueEnum, Clone, Copy, Debug)]
+/// Parses a value from its name,
+/// as used in configuration files.
+fn from_name<'de, T: Deserialize<'de>>(name: &'de str) -> Result<T, NameError> {
+    T::deserialize(name.into_deserializer())
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
 pub enum SpecialSubjectTypeOrder {
     Owl,
     Skos,
@@ -351,7 +366,17 @@ impl SpecialSubjectTypeOrder {
     }
 }
 
-#[derive(ValueEnum, Clone, Copy, Debug)]
+impl FromStr for SpecialSubjectTypeOrder {
+    type Err = NameError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        from_name(name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
 pub enum SpecialPredicateOrder {
     Owl,
     Skos,
@@ -373,6 +398,38 @@ impl SpecialPredicateOrder {
     }
 }
 
+impl FromStr for SpecialPredicateOrder {
+    type Err = NameError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        from_name(name)
+    }
+}
+
+/// Which line endings to write.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum EndOfLine {
+    /// Keep the style of the input,
+    /// as found at the end of its first line.
+    #[default]
+    Auto,
+    /// Line feed only (`\n`), common on Linux and macOS.
+    Lf,
+    /// Carriage return and line feed (`\r\n`), common on Windows.
+    Crlf,
+}
+
+impl FromStr for EndOfLine {
+    type Err = NameError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        from_name(name)
+    }
+}
+
+#[derive(Clone)]
 pub struct FormatOptions {
     /// Do not edit the file but only check if it already applies this tools format.
     pub check: bool,
@@ -510,6 +567,10 @@ pub struct FormatOptions {
     /// If you still want that,
     /// you have to manually add include it in this list.
     pub predicate_order: Option<Vec<String>>,
+    /// Which line endings to write.
+    ///
+    /// A byte order mark at the start of the input is always kept.
+    pub end_of_line: EndOfLine,
 }
 
 impl Default for FormatOptions {
@@ -530,11 +591,29 @@ impl Default for FormatOptions {
             subject_type_order: None,
             predicate_order_preset: None,
             predicate_order: None,
+            end_of_line: EndOfLine::Auto,
         }
     }
 }
 
 impl FormatOptions {
+    /// The options to use when embedding the formatter into another tool,
+    /// like an editor plugin or the bindings to other languages.
+    ///
+    /// These are the defaults of the CLI,
+    /// except that nothing gets checked or warned about,
+    /// as there is usually nobody to see such warnings.
+    #[must_use]
+    pub fn embedded() -> Self {
+        Self {
+            check: false,
+            sparql_syntax: true,
+            canonicalize: false,
+            warn_unsupported_numbers: false,
+            ..Self::default()
+        }
+    }
+
     #[must_use]
     pub fn subject_type_order(&self) -> Option<Vec<String>> {
         self.subject_type_order.clone().or_else(|| {
@@ -556,3 +635,157 @@ impl FormatOptions {
             .unwrap_or_else(|| vec!["rdf:type".to_string()])
     }
 }
+
+/// All the [`FormatOptions`] that may be set in a configuration file,
+/// each of them optional.
+///
+/// This allows to layer multiple sources of options on top of each other.
+/// See the corresponding fields of [`FormatOptions`] for documentation.
+/// [`FormatOptions::force`] is left out on purpose,
+/// as overwriting data should never be a project setting.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct PartialFormatOptions {
+    /// Number of spaces per level of indentation.
+    pub indentation: Option<u8>,
+    pub single_leafed_new_lines: Option<bool>,
+    pub generate_sorting_ids: Option<bool>,
+    pub prioritize_input_order: Option<bool>,
+    pub prtr_sorting: Option<bool>,
+    pub sparql_syntax: Option<bool>,
+    pub max_nesting: Option<bool>,
+    pub canonicalize: Option<bool>,
+    pub warn_unsupported_numbers: Option<bool>,
+    pub subject_type_order_preset: Option<SpecialSubjectTypeOrder>,
+    pub subject_type_order: Option<Vec<String>>,
+    pub predicate_order_preset: Option<SpecialPredicateOrder>,
+    pub predicate_order: Option<Vec<String>>,
+    pub end_of_line: Option<EndOfLine>,
+}
+
+impl PartialFormatOptions {
+    /// Overwrites the values in `options` with those set in `self`.
+    ///
+    /// Setting either the list or the preset of an order
+    /// also clears the other one in `options`.
+    pub fn apply_to(&self, options: &mut FormatOptions) {
+        if let Some(indentation) = self.indentation {
+            options.indentation = " ".repeat(indentation.into());
+        }
+        if let Some(val) = self.single_leafed_new_lines {
+            options.single_leafed_new_lines = val;
+        }
+        if let Some(val) = self.generate_sorting_ids {
+            options.generate_sorting_ids = val;
+        }
+        if let Some(val) = self.prioritize_input_order {
+            options.prioritize_input_order = val;
+        }
+        if let Some(val) = self.prtr_sorting {
+            options.prtr_sorting = val;
+        }
+        if let Some(val) = self.sparql_syntax {
+            options.sparql_syntax = val;
+        }
+        if let Some(val) = self.max_nesting {
+            options.max_nesting = val;
+        }
+        if let Some(val) = self.canonicalize {
+            options.canonicalize = val;
+        }
+        if let Some(val) = self.warn_unsupported_numbers {
+            options.warn_unsupported_numbers = val;
+        }
+        // A list and a preset are two ways to set the same order,
+        // so setting only one of them replaces the other one of lower layers.
+        if self.subject_type_order_preset.is_some() || self.subject_type_order.is_some() {
+            options.subject_type_order_preset = self.subject_type_order_preset;
+            options
+                .subject_type_order
+                .clone_from(&self.subject_type_order);
+        }
+        if self.predicate_order_preset.is_some() || self.predicate_order.is_some() {
+            options.predicate_order_preset = self.predicate_order_preset;
+            options.predicate_order.clone_from(&self.predicate_order);
+        }
+        if let Some(val) = self.end_of_line {
+            options.end_of_line = val;
+        }
+    }
+}
+
+impl From<&FormatOptions> for PartialFormatOptions {
+    fn from(options: &FormatOptions) -> Self {
+        Self {
+            indentation: u8::try_from(options.indentation.chars().count()).ok(),
+            single_leafed_new_lines: Some(options.single_leafed_new_lines),
+            generate_sorting_ids: Some(options.generate_sorting_ids),
+            prioritize_input_order: Some(options.prioritize_input_order),
+            prtr_sorting: Some(options.prtr_sorting),
+            sparql_syntax: Some(options.sparql_syntax),
+            max_nesting: Some(options.max_nesting),
+            canonicalize: Some(options.canonicalize),
+            warn_unsupported_numbers: Some(options.warn_unsupported_numbers),
+            subject_type_order_preset: options.subject_type_order_preset,
+            subject_type_order: options.subject_type_order.clone(),
+            predicate_order_preset: options.predicate_order_preset,
+            predicate_order: options.predicate_order.clone(),
+            end_of_line: Some(options.end_of_line),
+        }
+    }
+}
+
+/// Options concerning which content is processed and where results go to,
+/// as opposed to [`FormatOptions`],
+/// which concern how the content is formatted.
+#[derive(Default)]
+pub struct RunOptions {
+    /// The path to assume for content read from stdin.
+    ///
+    /// It is used in error messages and to look up configuration files,
+    /// as the actual file is never accessed.
+    pub stdin_filepath: Option<PathBuf>,
+    /// Write the formatted content to this file,
+    /// instead of back to the (single) input file or stdout.
+    pub output: Option<PathBuf>,
+    /// Write the formatted content into this directory,
+    /// instead of back to the input files.
+    ///
+    /// The directory structure of the input is mirrored,
+    /// relative to each source directory.
+    pub out_dir: Option<PathBuf>,
+    /// How many files to process concurrently.
+    ///
+    /// If `None`, the available parallelism of the machine is used.
+    pub jobs: Option<NonZeroUsize>,
+    /// Only format files within source directories matching one of these globs.
+    ///
+    /// The globs are matched relative to each source directory.
+    /// Files ignored by `.gitignore` and `.prttlignore` files,
+    /// as well as hidden ones, are still skipped.
+    pub include: Vec<String>,
+    /// Do not format files within source directories matching one of these globs,
+    /// in addition to those ignored by `.gitignore` and `.prttlignore` files.
+    ///
+    /// The globs are matched relative to each source directory.
+    pub exclude: Vec<String>,
+    /// Whether to follow symbolic links when searching source directories.
+    pub follow_symlinks: bool,
+    /// Whether to convert files of other RDF serializations to Turtle,
+    /// instead of formatting Turtle files.
+    ///
+    /// The format of each file is guessed from its extension
+    /// (see [`crate::parser::InputFormat`]),
+    /// and the result is written to a Turtle file next to it,
+    /// unless [`Self::output`] or [`Self::out_dir`] is set.
+    pub convert: bool,
+    /// Where to record which content is formatted already,
+    /// so it can be skipped on later runs
+    /// (see [`crate::cache`]).
+    ///
+    /// If `None`, no cache is used.
+    pub cache_dir: Option<PathBuf>,
+    /// Keep the previous content of each file that gets overwritten,
+    /// in a file with this suffix appended to its name.
+    pub backup_suffix: Option<String>,
+}
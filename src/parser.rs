@@ -4,15 +4,27 @@
 
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
-    rc::Rc,
+    ops::Range,
+    path::Path,
+    sync::Arc,
 };
 
-use oxrdf::{Graph, TermRef};
-use oxttl::TurtleParser;
+use oxjsonld::JsonLdParser;
+use oxrdf::{
+    BlankNode, Dataset, Graph, GraphName, GraphNameRef, Literal, NamedNode, NamedOrBlankNode,
+    NamedOrBlankNodeRef, Term, TermRef, Triple, TripleRef,
+};
+use oxrdfxml::RdfXmlParser;
+use oxttl::{NTriplesParser, TextPosition, TriGParser, TurtleParser};
 
 use thiserror::Error;
 
-use crate::{constants::SUBSTITUTE_BASE, input::Input, options::FormatOptions};
+use crate::{
+    constants::SUBSTITUTE_BASE,
+    input::{DatasetInput, DatasetShared, Input},
+    options::FormatOptions,
+    vocab::prtr,
+};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -80,6 +92,57 @@ For more information, see:
 
     #[error("Failed to parse as base IRI: '{0}'")]
     BaseIri(#[from] oxrdf::IriParseError),
+
+    #[error(transparent)]
+    RdfXmlSyntaxError(#[from] oxrdfxml::RdfXmlSyntaxError),
+
+    #[error(transparent)]
+    JsonLdSyntaxError(#[from] oxjsonld::JsonLdSyntaxError),
+
+    #[error(
+        "The input contains named graphs, which can not be represented in Turtle. \
+Consider converting to TriG instead."
+    )]
+    NamedGraph,
+}
+
+impl Error {
+    /// A short, stable, machine-readable identifier for the kind of this error.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Canonicalization(_) => "canonicalization",
+            Self::PrefixRedefinition(_) => "prefix-redefinition",
+            Self::MultiplePrefixesForNamespace(_) => "multiple-prefixes-for-namespace",
+            Self::Comment => "comment",
+            Self::BaseRedefinition => "base-redefinition",
+            Self::PrefixAndBaseShareNamespace(_) => "prefix-and-base-share-namespace",
+            Self::TurtleSyntaxError(_) => "turtle-syntax",
+            Self::BaseIri(_) => "base-iri",
+            Self::RdfXmlSyntaxError(_) => "rdf-xml-syntax",
+            Self::JsonLdSyntaxError(_) => "json-ld-syntax",
+            Self::NamedGraph => "named-graph",
+        }
+    }
+
+    /// Where in the input this error occurred, if known.
+    /// Lines and columns are 0-based.
+    #[must_use]
+    pub fn location(&self) -> Option<Range<TextPosition>> {
+        match self {
+            Self::TurtleSyntaxError(err) => Some(err.location()),
+            Self::Canonicalization(_)
+            | Self::PrefixRedefinition(_)
+            | Self::MultiplePrefixesForNamespace(_)
+            | Self::Comment
+            | Self::BaseRedefinition
+            | Self::PrefixAndBaseShareNamespace(_)
+            | Self::BaseIri(_)
+            | Self::RdfXmlSyntaxError(_)
+            | Self::JsonLdSyntaxError(_)
+            | Self::NamedGraph => None,
+        }
+    }
 }
 
 fn find_duplicate_values(map: &BTreeMap<String, String>) -> HashMap<String, Vec<String>> {
@@ -102,6 +165,252 @@ fn find_duplicate_values(map: &BTreeMap<String, String>) -> HashMap<String, Vec<
         .collect::<HashMap<_, _>>()
 }
 
+/// The extension of Turtle files, which we write.
+pub const TURTLE_EXTENSION: &str = "ttl";
+
+/// The extension of TriG files, which we write.
+pub const TRIG_EXTENSION: &str = "trig";
+
+/// The RDF serializations we can read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    Turtle,
+    TriG,
+    NTriples,
+    RdfXml,
+    JsonLd,
+}
+
+impl InputFormat {
+    /// The formats we format in place.
+    pub const FORMATTABLE: [Self; 2] = [Self::Turtle, Self::TriG];
+
+    /// The formats that can be converted to Turtle,
+    /// which excludes Turtle its self.
+    pub const CONVERTIBLE: [Self; 3] = [Self::NTriples, Self::RdfXml, Self::JsonLd];
+
+    /// The file extensions commonly used for this format,
+    /// in lower-case and without the leading dot.
+    #[must_use]
+    pub const fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Turtle => &[TURTLE_EXTENSION],
+            Self::TriG => &[TRIG_EXTENSION],
+            Self::NTriples => &["nt"],
+            Self::RdfXml => &["rdf", "owl"],
+            Self::JsonLd => &["jsonld"],
+        }
+    }
+
+    /// Guesses the format of a file from its extension.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::FORMATTABLE
+            .into_iter()
+            .chain(Self::CONVERTIBLE)
+            .find(|format| format.extensions().contains(&extension.as_str()))
+    }
+}
+
+/// Collects the triples, base and prefixes of a document,
+/// independent of its serialization,
+/// and validates them on the way.
+#[derive(Default)]
+struct InputBuilder {
+    graph: Graph,
+    base: Option<String>,
+    prefixes: HashMap<String, String>,
+    seen_subjects: HashSet<NamedOrBlankNode>,
+    subjects_in_order: Vec<NamedOrBlankNode>,
+    seen_bn_objects: HashSet<BlankNode>,
+    bn_objects_input_order: Vec<BlankNode>,
+}
+
+impl InputBuilder {
+    fn add_triple(&mut self, triple: &Triple) {
+        if self.seen_subjects.insert(triple.subject.clone()) {
+            self.subjects_in_order.push(triple.subject.clone());
+        }
+
+        // NOTE We do the ref and then into_owned again,
+        //      because this way we do not clone all the (potentially huge) objects.
+        if let TermRef::BlankNode(bn) = triple.object.as_ref() {
+            if self.seen_bn_objects.insert(bn.into_owned()) {
+                self.bn_objects_input_order.push(bn.into_owned());
+            }
+        }
+
+        self.graph.insert(triple);
+    }
+
+    /// Validates & stores the base IRI.
+    fn set_base(&mut self, cur_base: &str) -> Result<(), Error> {
+        if let Some(base_val) = &self.base {
+            if base_val != cur_base {
+                return Err(Error::BaseRedefinition);
+            }
+        }
+        self.base = Some(cur_base.to_owned());
+        Ok(())
+    }
+
+    /// Validates & stores a prefix.
+    fn add_prefix(&mut self, prefix: &str, namespace: &str) -> Result<(), Error> {
+        if let Some(cur_val) = self.prefixes.get(prefix) {
+            if cur_val != namespace {
+                return Err(Error::PrefixRedefinition(prefix.to_owned()));
+            }
+        } else {
+            self.prefixes
+                .insert(prefix.to_owned(), namespace.to_owned());
+        }
+        Ok(())
+    }
+
+    fn finish(self, options: &FormatOptions) -> Result<Input, Error> {
+        let bn_mapping = if options.canonicalize {
+            Some(rdf_canon::issue_graph(&self.graph)?)
+        } else {
+            None
+        };
+        self.finish_with(bn_mapping.as_ref(), None)
+    }
+
+    /// Like [`Self::finish`],
+    /// but with the blank node relabeling given from the outside,
+    /// as required when this is one of the graphs of a dataset.
+    fn finish_with(
+        self,
+        bn_mapping: Option<&HashMap<String, String>>,
+        dataset: Option<Arc<DatasetShared>>,
+    ) -> Result<Input, Error> {
+        let Self {
+            mut graph,
+            base,
+            prefixes,
+            mut subjects_in_order,
+            mut bn_objects_input_order,
+            ..
+        } = self;
+
+        if let Some(bn_mapping) = bn_mapping {
+            graph = rdf_canon::relabel_graph(&graph, bn_mapping)?;
+            subjects_in_order = subjects_in_order
+                .iter()
+                .map(|subj| rdf_canon::api::relabel_subject(subj.as_ref(), bn_mapping))
+                .collect::<Result<Vec<_>, _>>()?;
+            bn_objects_input_order = bn_objects_input_order
+                .iter()
+                .map(|bn| rdf_canon::api::relabel_blank_node(bn.as_ref(), bn_mapping))
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        let prefixes_sorted = BTreeMap::from_iter(prefixes.clone());
+        let prefixes_inverted: HashMap<String, String> =
+            prefixes.into_iter().map(|(k, v)| (v, k)).collect();
+        if prefixes_sorted.len() > prefixes_inverted.len() {
+            let duplicate_prefixes = find_duplicate_values(&prefixes_sorted);
+            return Err(Error::MultiplePrefixesForNamespace(duplicate_prefixes));
+        }
+
+        if let Some(base_val) = &base {
+            if let Some(prefix) = prefixes_inverted.get(base_val) {
+                return Err(Error::PrefixAndBaseShareNamespace(prefix.to_owned()));
+            }
+        }
+
+        Ok(Input {
+            base,
+            prefixes: prefixes_sorted,
+            prefixes_inverted,
+            subjects_in_order,
+            bn_objects_input_order,
+            graph,
+            dataset,
+        })
+    }
+}
+
+/// Fails with [`Error::Comment`] if comments were seen in the input,
+/// unless we are forced to continue.
+fn check_comments(seen_comment: bool, options: &FormatOptions) -> Result<(), Error> {
+    if seen_comment {
+        if options.force {
+            tracing::info!(
+                "Even though comments were found in the input,
+we continue formatting (which removes all of them),
+because the 'force' option was specified!"
+            );
+        } else {
+            return Err(Error::Comment);
+        }
+    }
+    Ok(())
+}
+
+/// The byte range of the first Turtle syntax comment in the text.
+pub(crate) fn find_comment(text: &str) -> Option<Range<usize>> {
+    let mut chars = text.char_indices();
+    while let Some((index, chr)) = chars.next() {
+        match chr {
+            '#' => {
+                let end = text
+                    .get(index..)
+                    .and_then(|rest| rest.find('\n'))
+                    .map_or(text.len(), |length| index + length);
+                return Some(index..end.min(text.len()));
+            }
+            '\\' => {
+                chars.next();
+            }
+            '<' => {
+                let quoted_triple = text
+                    .get(index + 1..)
+                    .is_some_and(|rest| rest.starts_with('<'));
+                if quoted_triple {
+                    // "<<" opens a quoted triple, not an IRI
+                    chars.next();
+                } else {
+                    for (_index, chr) in chars.by_ref() {
+                        if chr == '>' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' | '\'' => {
+                let quotes = chr.to_string().repeat(3);
+                let long = text
+                    .get(index..)
+                    .is_some_and(|rest| rest.starts_with(&quotes));
+                if long {
+                    chars.next();
+                    chars.next();
+                }
+                while let Some((index, inner)) = chars.next() {
+                    if inner == '\\' {
+                        chars.next();
+                    } else if inner == chr
+                        && (!long
+                            || text
+                                .get(index..)
+                                .is_some_and(|rest| rest.starts_with(&quotes)))
+                    {
+                        if long {
+                            chars.next();
+                            chars.next();
+                        }
+                        break;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
 /// Parses a given (supposedly) Turtle file content into an [`Input`],
 /// which can then be fed into [`crate::formatter::format`].
 ///
@@ -112,8 +421,8 @@ fn find_duplicate_values(map: &BTreeMap<String, String>) -> HashMap<String, Vec<
 /// - [`Error::PrefixRedefinition`]
 /// - [`Error::MultiplePrefixesForNamespace`]
 /// - [`Error::PrefixAndBaseShareNamespace`]
-pub fn parse(turtle_str: &[u8], options: &Rc<FormatOptions>) -> Result<Input, Error> {
-    let mut graph = Graph::new();
+pub fn parse(turtle_str: &[u8], options: &Arc<FormatOptions>) -> Result<Input, Error> {
+    let mut builder = InputBuilder::default();
 
     let mut parser = TurtleParser::new()
         .with_base_iri(SUBSTITUTE_BASE)?
@@ -123,98 +432,296 @@ pub fn parse(turtle_str: &[u8], options: &Rc<FormatOptions>) -> Result<Input, Er
     }
     parser.extend_from_slice(turtle_str.as_ref());
     parser.end();
-    let mut base = None;
-    let mut prefixes = HashMap::new();
-    let mut seen_subjects = HashSet::new();
-    let mut subjects_in_order = Vec::new();
-    let mut seen_bn_objects = HashSet::new();
-    let mut bn_objects_input_order = Vec::new();
     while let Some(triple_res) = parser.parse_next() {
-        let triple = triple_res?;
+        builder.add_triple(&triple_res?);
+        if let Some(cur_base) = parser.base_iri() {
+            builder.set_base(cur_base)?;
+        }
+        for (prefix, namespace) in parser.prefixes() {
+            builder.add_prefix(prefix, namespace)?;
+        }
+    }
+    // handle case of Turtle syntax comments found in the source
+    check_comments(parser.seen_comment(), options)?;
+    tracing::debug!("Low level parsing went ok!");
+    builder.finish(options)
+}
+
+/// Parses a given (supposedly) TriG file content into a [`DatasetInput`],
+/// which can then be fed into [`crate::formatter::format_dataset`].
+///
+/// All graphs share the base and prefixes of the document.
+/// Blank nodes are scoped to the whole dataset,
+/// so canonicalization happens on the dataset level.
+///
+/// # Errors
+///
+/// The same as [`parse`].
+pub fn parse_trig(trig_str: &[u8], options: &Arc<FormatOptions>) -> Result<DatasetInput, Error> {
+    // Only collects base and prefixes
+    let mut namespaces = InputBuilder::default();
+    let mut graphs: HashMap<GraphName, InputBuilder> = HashMap::new();
+    let mut dataset = Dataset::new();
 
-        if seen_subjects.insert(triple.subject.clone()) {
-            subjects_in_order.push(triple.subject.clone());
+    let mut parser = TriGParser::new()
+        .with_base_iri(SUBSTITUTE_BASE)?
+        .low_level();
+    if let Some(parse_res) = parser.parse_next() {
+        parse_res?;
+    }
+    parser.extend_from_slice(trig_str.as_ref());
+    parser.end();
+    while let Some(quad_res) = parser.parse_next() {
+        let quad = quad_res?;
+        dataset.insert(&quad);
+        graphs
+            .entry(quad.graph_name.clone())
+            .or_default()
+            .add_triple(&Triple::from(quad));
+        if let Some(cur_base) = parser.base_iri() {
+            namespaces.set_base(cur_base)?;
+        }
+        for (prefix, namespace) in parser.prefixes() {
+            namespaces.add_prefix(prefix, namespace)?;
         }
+    }
+    // The low-level TriG parser does not keep track of comments
+    let seen_comment = find_comment(&String::from_utf8_lossy(trig_str)).is_some();
+    check_comments(seen_comment, options)?;
+    tracing::debug!("Low level parsing went ok!");
 
-        // NOTE We do the ref and then into_owned again,
-        //      because this way we do not clone all the (potentially huge) objects.
-        if let TermRef::BlankNode(bn) = triple.object.as_ref() {
-            if seen_bn_objects.insert(bn.into_owned()) {
-                bn_objects_input_order.push(bn.into_owned());
+    let bn_mapping = if options.canonicalize {
+        Some(rdf_canon::issue(&dataset)?)
+    } else {
+        None
+    };
+    let dataset = Arc::new(dataset_shared(&dataset, bn_mapping.as_ref())?);
+
+    let mut default_graph = None;
+    let mut named_graphs: Vec<(NamedOrBlankNode, Input)> = Vec::new();
+    for (graph_name, mut builder) in graphs {
+        builder.base.clone_from(&namespaces.base);
+        builder.prefixes.clone_from(&namespaces.prefixes);
+        let input = builder.finish_with(bn_mapping.as_ref(), Some(Arc::clone(&dataset)))?;
+        match graph_name {
+            GraphName::DefaultGraph => default_graph = Some(input),
+            GraphName::NamedNode(name) => named_graphs.push((name.into(), input)),
+            GraphName::BlankNode(name) => {
+                let name = bn_mapping.as_ref().map_or_else(
+                    || Ok(name.clone()),
+                    |bn_mapping| rdf_canon::api::relabel_blank_node(name.as_ref(), bn_mapping),
+                )?;
+                named_graphs.push((name.into(), input));
             }
         }
+    }
+    named_graphs.sort_by_cached_key(|(name, _input)| name.to_string());
 
-        graph.insert(&triple);
+    let default_graph =
+        default_graph.map_or_else(|| namespaces.finish_with(None, Some(dataset)), Ok)?;
+    Ok(DatasetInput {
+        default_graph,
+        named_graphs,
+    })
+}
 
-        // validate & store base
-        if let Some(cur_base) = parser.base_iri() {
-            if let Some(base_val) = base {
-                if base_val != cur_base {
-                    return Err(Error::BaseRedefinition);
-                }
-            }
-            base = Some(cur_base.to_owned());
+/// Collects what the graphs of a dataset share,
+/// using the canonical blank node labels if a mapping is given.
+fn dataset_shared(
+    dataset: &Dataset,
+    bn_mapping: Option<&HashMap<String, String>>,
+) -> Result<DatasetShared, Error> {
+    let relabeled = bn_mapping
+        .map(|bn_mapping| rdf_canon::relabel(dataset, bn_mapping))
+        .transpose()?;
+    let dataset = relabeled.as_ref().unwrap_or(dataset);
+
+    let mut sorting_ids = Graph::new();
+    let mut graphs_of_blank_node: HashMap<BlankNode, HashSet<GraphName>> = HashMap::new();
+    let mut blank_nodes = HashSet::new();
+    for quad in dataset {
+        if quad.predicate == *prtr::SORTING_ID {
+            sorting_ids.insert(TripleRef::from(quad));
+        }
+        if let GraphNameRef::BlankNode(bn) = quad.graph_name {
+            blank_nodes.insert(bn.into_owned());
+        }
+        let subject_bn = match quad.subject {
+            NamedOrBlankNodeRef::BlankNode(bn) => Some(bn),
+            NamedOrBlankNodeRef::NamedNode(_) => None,
+        };
+        let object_bn = match quad.object {
+            TermRef::BlankNode(bn) => Some(bn),
+            TermRef::NamedNode(_) | TermRef::Literal(_) | TermRef::Triple(_) => None,
+        };
+        for bn in subject_bn.into_iter().chain(object_bn) {
+            graphs_of_blank_node
+                .entry(bn.into_owned())
+                .or_default()
+                .insert(quad.graph_name.into_owned());
         }
+    }
+    blank_nodes.extend(
+        graphs_of_blank_node
+            .into_iter()
+            .filter(|(_bn, graphs)| graphs.len() > 1)
+            .map(|(bn, _graphs)| bn),
+    );
 
-        // validate & store prefixes
-        for cur_prefix in parser.prefixes() {
-            if let Some(cur_val) = prefixes.get(cur_prefix.0) {
-                if cur_val != cur_prefix.1 {
-                    return Err(Error::PrefixRedefinition(cur_prefix.0.to_owned()));
-                }
-            } else {
-                prefixes.insert(cur_prefix.0.to_owned(), cur_prefix.1.to_owned());
+    Ok(DatasetShared {
+        sorting_ids,
+        blank_nodes,
+    })
+}
+
+/// Parses a given N-Triples file content into an [`Input`].
+///
+/// N-Triples has no notion of base or prefixes,
+/// so the result uses neither of them.
+fn parse_ntriples(content: &[u8], options: &FormatOptions) -> Result<Input, Error> {
+    let mut builder = InputBuilder::default();
+    for triple_res in NTriplesParser::new().for_slice(content) {
+        builder.add_triple(&triple_res?);
+    }
+    builder.finish(options)
+}
+
+/// Converts a triple of the upstream `oxrdf`,
+/// as produced by the RDF/XML and JSON-LD parsers,
+/// to one of the `oxrdf` we use.
+fn from_upstream(triple: oxrdf_upstream::Triple) -> Triple {
+    let subject: NamedOrBlankNode = match triple.subject {
+        oxrdf_upstream::NamedOrBlankNode::NamedNode(node) => {
+            NamedNode::new_unchecked(node.into_string()).into()
+        }
+        oxrdf_upstream::NamedOrBlankNode::BlankNode(node) => {
+            BlankNode::new_unchecked(node.into_string()).into()
+        }
+    };
+    let object: Term = match triple.object {
+        oxrdf_upstream::Term::NamedNode(node) => NamedNode::new_unchecked(node.into_string()).into(),
+        oxrdf_upstream::Term::BlankNode(node) => BlankNode::new_unchecked(node.into_string()).into(),
+        oxrdf_upstream::Term::Literal(literal) => match literal.destruct() {
+            (value, _, Some(language)) => {
+                Literal::new_language_tagged_literal_unchecked(value, language)
+            }
+            (value, Some(datatype), None) => {
+                Literal::new_typed_literal(value, NamedNode::new_unchecked(datatype.into_string()))
             }
+            (value, None, None) => Literal::new_simple_literal(value),
+        }
+        .into(),
+    };
+    Triple::new(
+        subject,
+        NamedNode::new_unchecked(triple.predicate.into_string()),
+        object,
+    )
+}
+
+/// Parses a given RDF/XML file content into an [`Input`],
+/// keeping the namespace declarations as prefixes.
+fn parse_rdf_xml(content: &[u8], options: &FormatOptions) -> Result<Input, Error> {
+    let mut builder = InputBuilder::default();
+    let mut parser = RdfXmlParser::new()
+        .with_base_iri(SUBSTITUTE_BASE)?
+        .for_slice(content);
+    while let Some(triple_res) = parser.next() {
+        builder.add_triple(&from_upstream(triple_res?));
+        if let Some(cur_base) = parser.base_iri() {
+            builder.set_base(cur_base)?;
+        }
+        for (prefix, namespace) in parser.prefixes() {
+            builder.add_prefix(prefix, namespace)?;
         }
     }
-    // handle case of Turtle syntax comments found in the source
-    if parser.seen_comment() {
-        if options.force {
-            tracing::info!(
-                "Even though comments were found in the input,
-we continue formatting (which removes all of them),
-because the 'force' option was specified!"
-            );
-        } else {
-            return Err(Error::Comment);
+    builder.finish(options)
+}
+
+/// Parses a given JSON-LD file content into an [`Input`],
+/// keeping the prefixes defined in the `@context`.
+///
+/// Only the default graph is supported.
+fn parse_json_ld(content: &[u8], options: &FormatOptions) -> Result<Input, Error> {
+    let mut builder = InputBuilder::default();
+    let mut parser = JsonLdParser::new()
+        .with_base_iri(SUBSTITUTE_BASE)?
+        .for_slice(content);
+    while let Some(quad_res) = parser.next() {
+        let quad = quad_res?;
+        if !quad.graph_name.is_default_graph() {
+            return Err(Error::NamedGraph);
+        }
+        builder.add_triple(&from_upstream(quad.into()));
+        if let Some(cur_base) = parser.base_iri() {
+            builder.set_base(cur_base)?;
+        }
+        for (prefix, namespace) in parser.prefixes() {
+            builder.add_prefix(prefix, namespace)?;
         }
     }
-    tracing::debug!("Low level parsing went ok!");
-    if options.canonicalize {
-        let bn_mapping = rdf_canon::issue_graph(&graph)?;
-        graph = rdf_canon::relabel_graph(&graph, &bn_mapping)?;
-        subjects_in_order = subjects_in_order
-            .iter()
-            .map(|subj| rdf_canon::api::relabel_subject(subj.as_ref(), &bn_mapping))
-            .collect::<Result<Vec<_>, _>>()?;
-        bn_objects_input_order = bn_objects_input_order
-            .iter()
-            .map(|bn| rdf_canon::api::relabel_blank_node(bn.as_ref(), &bn_mapping))
-            .collect::<Result<Vec<_>, _>>()?;
-    }
-
-    let prefixes_sorted = BTreeMap::from_iter(prefixes.clone());
-    let prefixes_inverted: HashMap<String, String> =
-        prefixes.into_iter().map(|(k, v)| (v, k)).collect();
-    if prefixes_sorted.len() > prefixes_inverted.len() {
-        let duplicate_prefixes = find_duplicate_values(&prefixes_sorted);
-        return Err(Error::MultiplePrefixesForNamespace(duplicate_prefixes));
-    }
-
-    if let Some(base_val) = &base {
-        if let Some(prefix) = prefixes_inverted.get(base_val) {
-            return Err(Error::PrefixAndBaseShareNamespace(prefix.to_owned()));
-        }
-    }
-
-    let input = Input {
-        base,
-        prefixes: prefixes_sorted,
-        prefixes_inverted,
-        subjects_in_order,
-        bn_objects_input_order,
-        graph,
-    };
+    builder.finish(options)
+}
+
+/// Parses a given file content of the given format into an [`Input`],
+/// which can then be fed into [`crate::formatter::format`].
+///
+/// # Errors
+///
+/// - any error from [`parse`] for Turtle
+/// - any error from [`parse_trig`] and [`Error::NamedGraph`] for TriG
+/// - [`Error::TurtleSyntaxError`] for N-Triples
+/// - [`Error::RdfXmlSyntaxError`] for RDF/XML
+/// - [`Error::JsonLdSyntaxError`] and [`Error::NamedGraph`] for JSON-LD
+/// - [`Error::BaseRedefinition`], [`Error::PrefixRedefinition`],
+///   [`Error::MultiplePrefixesForNamespace`] and [`Error::PrefixAndBaseShareNamespace`]
+///   for all formats that support base and prefixes
+pub fn parse_as(
+    content: &[u8],
+    format: InputFormat,
+    options: &Arc<FormatOptions>,
+) -> Result<Input, Error> {
+    match format {
+        InputFormat::Turtle => parse(content, options),
+        InputFormat::TriG => {
+            let dataset = parse_trig(content, options)?;
+            if !dataset.named_graphs.is_empty() {
+                return Err(Error::NamedGraph);
+            }
+            Ok(dataset.default_graph)
+        }
+        InputFormat::NTriples => parse_ntriples(content, options),
+        InputFormat::RdfXml => parse_rdf_xml(content, options),
+        InputFormat::JsonLd => parse_json_ld(content, options),
+    }
+}
 
-    Ok(input)
+/// Creates an [`Input`] from triples, base and prefixes
+/// that were not parsed from a single document,
+/// like the result of a merge.
+///
+/// The triples are expected in input order.
+///
+/// # Errors
+///
+/// - [`Error::MultiplePrefixesForNamespace`]
+/// - [`Error::PrefixAndBaseShareNamespace`]
+/// - [`Error::Canonicalization`]
+pub fn input_from_parts<'a>(
+    triples: impl IntoIterator<Item = TripleRef<'a>>,
+    base: Option<&str>,
+    prefixes: &BTreeMap<String, String>,
+    options: &FormatOptions,
+) -> Result<Input, Error> {
+    let mut builder = InputBuilder::default();
+    for triple in triples {
+        builder.add_triple(&triple.into_owned());
+    }
+    if let Some(base) = base {
+        builder.set_base(base)?;
+    }
+    for (prefix, namespace) in prefixes {
+        builder.add_prefix(prefix, namespace)?;
+    }
+    builder.finish(options)
 }
@@ -0,0 +1,489 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! The outcome of a run of the formatter, for each processed file.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use diffy::{Patch, PatchFormatter};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::VERSION;
+use crate::error::Error;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_RULE_NEEDS_FORMATTING: &str = "needs-formatting";
+const INFORMATION_URI: &str = "https://codeberg.org/elevont/prttl";
+
+/// When to color the patches we print.
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum ColorChoice {
+    /// Only if the output is a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color output written to the given stream.
+    #[must_use]
+    pub fn use_color(self, stream: &impl IsTerminal) -> bool {
+        match self {
+            Self::Auto => stream.is_terminal(),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// How to write the report of a run.
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum ReportFormat {
+    /// Patches for the files that need formatting,
+    /// with errors going to the log.
+    #[default]
+    Human,
+    /// One JSON record per file.
+    Json,
+    /// [SARIF](https://sarifweb.azurewebsites.net/) v2.1.0,
+    /// as understood by code-scanning UIs.
+    Sarif,
+}
+
+/// A range within a source file.
+/// Lines and columns are 1-based.
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct Location {
+    pub start_line: u64,
+    pub start_column: u64,
+    pub end_line: u64,
+    pub end_column: u64,
+}
+
+impl Location {
+    fn of(err: &Error) -> Option<Self> {
+        err.location_1based().map(|range| Self {
+            start_line: range.start.line,
+            start_column: range.start.column,
+            end_line: range.end.line,
+            end_column: range.end.column,
+        })
+    }
+
+    fn to_sarif_region(self) -> Value {
+        json!({
+            "startLine": self.start_line,
+            "startColumn": self.start_column,
+            "endLine": self.end_line,
+            "endColumn": self.end_column,
+        })
+    }
+}
+
+/// What happened to a single file.
+#[derive(Debug)]
+pub enum Status {
+    /// The content was already formatted.
+    Unchanged,
+    /// The content was not yet formatted,
+    /// and the formatted version was written.
+    Formatted,
+    /// Only in check mode:
+    /// The content is not yet formatted.
+    /// Holds the (uncolored) unified patch that would format it.
+    NeedsFormatting(String),
+    /// Formatting is turned off for the file
+    /// with [`crate::SKIP_MARKER`],
+    /// so it was left as it is.
+    Skipped,
+    /// The file could not be processed.
+    Error(Error),
+}
+
+impl Status {
+    /// A short, stable, machine-readable name of the status.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Unchanged => "unchanged",
+            Self::Formatted => "formatted",
+            Self::NeedsFormatting(_) => "needs-formatting",
+            Self::Skipped => "skipped",
+            Self::Error(_) => "error",
+        }
+    }
+}
+
+/// The outcome for a single file.
+#[derive(Debug)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub status: Status,
+}
+
+/// An error, as reported in JSON.
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct JsonError {
+    pub(crate) kind: &'static str,
+    pub(crate) message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) location: Option<Location>,
+}
+
+impl From<&Error> for JsonError {
+    fn from(err: &Error) -> Self {
+        Self {
+            kind: err.kind(),
+            message: err.to_string(),
+            location: Location::of(err),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct JsonFileReport<'report> {
+    path: String,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<JsonError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    patch: Option<&'report str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct JsonReport<'report> {
+    files: Vec<JsonFileReport<'report>>,
+    summary: Summary,
+}
+
+/// Percent-encodes all the characters of a path segment
+/// that may not appear in a URI as they are.
+fn percent_encode(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push('%');
+            for digit in [byte >> 4, byte & 0xF] {
+                if let Some(digit) = char::from_digit(u32::from(digit), 16) {
+                    encoded.push(digit.to_ascii_uppercase());
+                }
+            }
+        }
+    }
+    encoded
+}
+
+/// Converts a path into the URI form used by SARIF:
+/// A relative reference for relative paths,
+/// and a `file` URI for absolute ones.
+fn to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let encoded = path
+        .split('/')
+        .map(|segment| {
+            // Keeps the colon of Windows drive letters
+            if segment.len() == 2 && segment.ends_with(':') {
+                segment.to_owned()
+            } else {
+                percent_encode(segment)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    if path.starts_with('/') {
+        format!("file://{encoded}")
+    } else if Path::new(&path).is_absolute() {
+        format!("file:///{encoded}")
+    } else {
+        encoded
+    }
+}
+
+/// Creates the SARIF description of the rule with the given ID,
+/// which is the kind of an error for all but [`SARIF_RULE_NEEDS_FORMATTING`].
+fn sarif_rule(rule_id: &str) -> Value {
+    let description = if rule_id == SARIF_RULE_NEEDS_FORMATTING {
+        "The file is not formatted".to_owned()
+    } else {
+        format!("The file could not be processed ({rule_id})")
+    };
+    json!({
+        "id": rule_id,
+        "shortDescription": { "text": description },
+        "helpUri": INFORMATION_URI,
+    })
+}
+
+impl FileReport {
+    fn to_json(&self) -> JsonFileReport<'_> {
+        let (error, patch) = match &self.status {
+            Status::Unchanged | Status::Formatted | Status::Skipped => (None, None),
+            Status::NeedsFormatting(patch) => (None, Some(patch.as_str())),
+            Status::Error(err) => (Some(JsonError::from(err)), None),
+        };
+        JsonFileReport {
+            path: self.path.display().to_string(),
+            status: self.status.name(),
+            error,
+            patch,
+        }
+    }
+
+    /// Creates the SARIF results for this file:
+    /// One per hunk of the patch if it needs formatting,
+    /// one if it could not be processed,
+    /// and none otherwise.
+    fn to_sarif_results(&self) -> Vec<Value> {
+        let uri = to_uri(&self.path);
+        let result = |rule_id: &str, level: &str, message: String, region: Option<Value>| {
+            let mut physical_location = json!({ "artifactLocation": { "uri": uri } });
+            if let (Some(region), Some(physical_location)) =
+                (region, physical_location.as_object_mut())
+            {
+                physical_location.insert("region".to_owned(), region);
+            }
+            json!({
+                "ruleId": rule_id,
+                "level": level,
+                "message": { "text": message },
+                "locations": [{ "physicalLocation": physical_location }],
+            })
+        };
+        match &self.status {
+            Status::Unchanged | Status::Formatted | Status::Skipped => vec![],
+            Status::NeedsFormatting(patch) => {
+                let Ok(parsed_patch) = Patch::from_str(patch) else {
+                    return vec![result(
+                        SARIF_RULE_NEEDS_FORMATTING,
+                        "warning",
+                        "The file is not formatted".to_owned(),
+                        None,
+                    )];
+                };
+                parsed_patch
+                    .hunks()
+                    .iter()
+                    .map(|hunk| {
+                        let range = hunk.old_range();
+                        let start_line = range.start().max(1);
+                        let end_line = range.end().saturating_sub(1).max(start_line);
+                        let region = json!({
+                            "startLine": start_line,
+                            "endLine": end_line,
+                        });
+                        result(
+                            SARIF_RULE_NEEDS_FORMATTING,
+                            "warning",
+                            format!("Lines {start_line} to {end_line} are not formatted"),
+                            Some(region),
+                        )
+                    })
+                    .collect()
+            }
+            Status::Error(err) => vec![result(
+                err.kind(),
+                "error",
+                err.to_string(),
+                Location::of(err).map(Location::to_sarif_region),
+            )],
+        }
+    }
+}
+
+/// Counts of the files in a [`Report`] that were not processed successfully,
+/// and of those that were skipped.
+#[derive(Serialize, Debug)]
+pub struct Summary {
+    pub total: usize,
+    pub needs_formatting: usize,
+    pub errors: usize,
+    pub skipped: usize,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.needs_formatting > 0 {
+            write!(
+                f,
+                "{} of {} files need formatting",
+                self.needs_formatting, self.total
+            )?;
+            if self.errors > 0 {
+                write!(f, ", ")?;
+            }
+        }
+        if self.errors > 0 {
+            write!(
+                f,
+                "{} of {} files could not be processed",
+                self.errors, self.total
+            )?;
+        }
+        if self.skipped > 0 {
+            if self.needs_formatting > 0 || self.errors > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} of {} files were skipped", self.skipped, self.total)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of a run of the formatter,
+/// in the order the files were given.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub files: Vec<FileReport>,
+}
+
+impl Report {
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary {
+            total: self.files.len(),
+            needs_formatting: 0,
+            errors: 0,
+            skipped: 0,
+        };
+        for file_report in &self.files {
+            match file_report.status {
+                Status::NeedsFormatting(_) => summary.needs_formatting += 1,
+                Status::Error(_) => summary.errors += 1,
+                Status::Skipped => summary.skipped += 1,
+                Status::Unchanged | Status::Formatted => (),
+            }
+        }
+        summary
+    }
+
+    /// Whether all files were processed successfully,
+    /// and - in check mode - were already formatted.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.files.iter().all(|file_report| {
+            matches!(
+                file_report.status,
+                Status::Unchanged | Status::Formatted | Status::Skipped
+            )
+        })
+    }
+
+    /// Writes the report as a single JSON document,
+    /// containing one record per file and a summary.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `out` fails.
+    pub fn write_json(&self, out: &mut impl Write) -> io::Result<()> {
+        let report = JsonReport {
+            files: self.files.iter().map(FileReport::to_json).collect(),
+            summary: self.summary(),
+        };
+        serde_json::to_writer_pretty(&mut *out, &report)?;
+        writeln!(out)
+    }
+
+    /// Writes the report as a SARIF v2.1.0 log.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `out` fails.
+    pub fn write_sarif(&self, out: &mut impl Write) -> io::Result<()> {
+        let results: Vec<Value> = self
+            .files
+            .iter()
+            .flat_map(FileReport::to_sarif_results)
+            .collect();
+        let mut rule_ids = BTreeSet::from([SARIF_RULE_NEEDS_FORMATTING]);
+        rule_ids.extend(self.files.iter().filter_map(|file_report| {
+            if let Status::Error(err) = &file_report.status {
+                Some(err.kind())
+            } else {
+                None
+            }
+        }));
+        let rules: Vec<Value> = rule_ids.into_iter().map(sarif_rule).collect();
+        let sarif = json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": VERSION,
+                        "informationUri": INFORMATION_URI,
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        });
+        serde_json::to_writer_pretty(&mut *out, &sarif)?;
+        writeln!(out)
+    }
+
+    /// Writes the human readable part of the report:
+    /// The patch for each file that needs formatting,
+    /// or - if `list_different` - only the paths of these files,
+    /// followed by the files that were skipped (see [`crate::SKIP_MARKER`]),
+    /// unless `list_different`.
+    ///
+    /// Errors are not included;
+    /// they are expected to be logged separately.
+    ///
+    /// # Errors
+    ///
+    /// If writing to `out` fails.
+    pub fn write_human(
+        &self,
+        out: &mut impl Write,
+        list_different: bool,
+        color: bool,
+    ) -> io::Result<()> {
+        for file_report in &self.files {
+            let Status::NeedsFormatting(patch) = &file_report.status else {
+                continue;
+            };
+            writeln!(out, "{}", file_report.path.display())?;
+            if list_different {
+                continue;
+            }
+            let colored_patch = Patch::from_str(patch)
+                .ok()
+                .filter(|_| color)
+                .map(|parsed_patch| {
+                    PatchFormatter::new()
+                        .with_color()
+                        .fmt_patch(&parsed_patch)
+                        .to_string()
+                });
+            write!(out, "{}", colored_patch.as_deref().unwrap_or(patch))?;
+        }
+        if list_different {
+            return Ok(());
+        }
+        for file_report in &self.files {
+            if matches!(file_report.status, Status::Skipped) {
+                writeln!(
+                    out,
+                    "{}: skipped, as formatting is turned off for it",
+                    file_report.path.display()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
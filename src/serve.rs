@@ -0,0 +1,601 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! A small HTTP service for formatting,
+//! to run as a local sidecar of editors and other tools,
+//! which then do not need to spawn a process per document.
+//!
+//! - `GET /health` answers with the status and version of the service.
+//! - `POST /format` formats the Turtle in the body,
+//!   or TriG with `Content-Type: application/trig`,
+//!   and answers with the formatted text.
+//!   With `Content-Type: application/json`, the body is an object
+//!   with the `text` to format, and optionally its `format` (`turtle` or `trig`)
+//!   and formatting `options`,
+//!   and the answer is an object with the formatted `text`,
+//!   and whether it `changed`.
+//!   Content starting with [`crate::SKIP_MARKER`] is answered unchanged,
+//!   and marked as `skipped` in JSON answers.
+//!
+//! Formatting options can be given in the query of the request
+//! and as the `options` of a JSON request,
+//! with the same names and values as in configuration files.
+//! They override the options resolved for the directory the service runs in.
+//!
+//! Errors are answered with an object holding the `kind`, `message`
+//! and - if known - `location` of the `error`,
+//! the same as in the JSON report of the CLI.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError, mpsc};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::config::Resolver;
+use crate::error::Error;
+use crate::options::{FormatOptions, PartialFormatOptions};
+use crate::parser::InputFormat;
+use crate::report::JsonError;
+use crate::{STDIN_SOURCE, VERSION, encoding, format_str_styled, is_skipped};
+
+/// The address to listen on, if none is given.
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:8080";
+/// The maximum size of a request body, in bytes, if none is given.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// The maximum size of the request line and headers together, in bytes.
+const MAX_HEAD_SIZE: u64 = 16 * 1024;
+/// How long to wait for a client to send or receive data.
+const TIMEOUT: Duration = Duration::from_secs(30);
+/// How many connections are answered concurrently.
+const WORKERS: usize = 4;
+/// How many accepted connections may wait for a worker,
+/// before we stop accepting further ones.
+const QUEUE_SIZE: usize = 64;
+
+const PATH_FORMAT: &str = "/format";
+const PATH_HEALTH: &str = "/health";
+
+const MEDIA_TYPE_JSON: &str = "application/json";
+const MEDIA_TYPE_TRIG: &str = "application/trig";
+
+/// The options that take a list of values,
+/// which are comma separated when given in the query.
+const LIST_OPTIONS: [&str; 2] = ["subject-type-order", "predicate-order"];
+
+/// The HTTP status codes we answer with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StatusCode {
+    Ok,
+    BadRequest,
+    NotFound,
+    MethodNotAllowed,
+    LengthRequired,
+    ContentTooLarge,
+    UnprocessableContent,
+    RequestHeaderFieldsTooLarge,
+    InternalServerError,
+}
+
+impl StatusCode {
+    const fn code(self) -> u16 {
+        match self {
+            Self::Ok => 200,
+            Self::BadRequest => 400,
+            Self::NotFound => 404,
+            Self::MethodNotAllowed => 405,
+            Self::LengthRequired => 411,
+            Self::ContentTooLarge => 413,
+            Self::UnprocessableContent => 422,
+            Self::RequestHeaderFieldsTooLarge => 431,
+            Self::InternalServerError => 500,
+        }
+    }
+
+    const fn reason(self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::BadRequest => "Bad Request",
+            Self::NotFound => "Not Found",
+            Self::MethodNotAllowed => "Method Not Allowed",
+            Self::LengthRequired => "Length Required",
+            Self::ContentTooLarge => "Content Too Large",
+            Self::UnprocessableContent => "Unprocessable Content",
+            Self::RequestHeaderFieldsTooLarge => "Request Header Fields Too Large",
+            Self::InternalServerError => "Internal Server Error",
+        }
+    }
+}
+
+/// The status to answer with for an error from formatting:
+/// Problems with the content are the fault of the client,
+/// everything else is ours.
+fn status_of(err: &Error) -> StatusCode {
+    match err {
+        Error::File(_, err) => status_of(err),
+        Error::MultipleBases
+        | Error::TurtleSyntaxError(_)
+        | Error::SortingIdGeneration(_)
+        | Error::InvalidUtf8(_, _)
+        | Error::ParseError(_) => StatusCode::UnprocessableContent,
+        Error::Check(_)
+        | Error::Format(_)
+        | Error::TargetFileDoesNotExist(_)
+        | Error::FailedToReadTargetFile(_, _)
+        | Error::Config(_)
+        | Error::FailedToWriteFormattedFile(_, _, _)
+        | Error::FailedToListFilesInInputDir(_, _, _)
+        | Error::FailedToWalkInputDir(_, _)
+        | Error::UnknownInputFormat(_)
+        | Error::InvalidGlob(_)
+        | Error::FailedToCreateTurtleStructure(_)
+        | Error::FailedToReadStdin(_)
+        | Error::FailedToWriteStdout(_)
+        | Error::OutputFileExists(_)
+        | Error::OutputForMultipleInputs(_)
+        | Error::FailedToCreateOutputDir(_, _)
+        | Error::FailedToWatch(_)
+        | Error::FailedToRunGit(_, _)
+        | Error::GitCommandFailed(_, _)
+        | Error::InvalidLspMessage(_)
+        | Error::FailedToListen(_, _) => StatusCode::InternalServerError,
+    }
+}
+
+/// A request that could not be answered successfully.
+struct HttpError {
+    status: StatusCode,
+    error: JsonError,
+    /// The methods supported on the requested path,
+    /// if the request used another one.
+    allow: Option<&'static str>,
+}
+
+impl HttpError {
+    const fn new(status: StatusCode, kind: &'static str, message: String) -> Self {
+        Self {
+            status,
+            error: JsonError {
+                kind,
+                message,
+                location: None,
+            },
+            allow: None,
+        }
+    }
+
+    const fn bad_request(message: String) -> Self {
+        Self::new(StatusCode::BadRequest, "bad-request", message)
+    }
+
+    const fn invalid_options(message: String) -> Self {
+        Self::new(StatusCode::BadRequest, "invalid-options", message)
+    }
+
+    fn method_not_allowed(method: &str, allow: &'static str) -> Self {
+        Self {
+            allow: Some(allow),
+            ..Self::new(
+                StatusCode::MethodNotAllowed,
+                "method-not-allowed",
+                format!("Method {method} is not supported here, only {allow}"),
+            )
+        }
+    }
+}
+
+impl From<&Error> for HttpError {
+    fn from(err: &Error) -> Self {
+        Self {
+            status: status_of(err),
+            error: JsonError::from(err),
+            allow: None,
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    /// The media type of the body, in lower case and without parameters.
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: StatusCode,
+    content_type: &'static str,
+    allow: Option<&'static str>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: StatusCode, value: &impl Serialize) -> Self {
+        Self {
+            status,
+            content_type: MEDIA_TYPE_JSON,
+            allow: None,
+            // Serializing our own values does not fail
+            body: serde_json::to_vec(value).unwrap_or_default(),
+        }
+    }
+
+    fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status.code(),
+            self.status.reason(),
+            self.content_type,
+            self.body.len(),
+        )?;
+        if let Some(allow) = self.allow {
+            write!(out, "Allow: {allow}\r\n")?;
+        }
+        out.write_all(b"\r\n")?;
+        out.write_all(&self.body)?;
+        out.flush()
+    }
+}
+
+impl From<HttpError> for Response {
+    fn from(err: HttpError) -> Self {
+        Self {
+            allow: err.allow,
+            ..Self::json(err.status, &json!({ "error": err.error }))
+        }
+    }
+}
+
+/// The format of the text in a JSON request.
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+enum TextFormat {
+    #[default]
+    Turtle,
+    Trig,
+}
+
+impl From<TextFormat> for InputFormat {
+    fn from(format: TextFormat) -> Self {
+        match format {
+            TextFormat::Turtle => Self::Turtle,
+            TextFormat::Trig => Self::TriG,
+        }
+    }
+}
+
+/// The body of a JSON request to format.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FormatRequest {
+    text: String,
+    #[serde(default)]
+    format: TextFormat,
+    #[serde(default)]
+    options: Map<String, Value>,
+}
+
+/// Reads a line of the request line and headers,
+/// without its line ending,
+/// keeping track of their total size.
+fn read_head_line(input: &mut impl BufRead, head_size: &mut u64) -> Result<String, HttpError> {
+    let mut line = String::new();
+    let len = input
+        .by_ref()
+        .take(MAX_HEAD_SIZE.saturating_sub(*head_size))
+        .read_line(&mut line)
+        .map_err(|err| HttpError::bad_request(format!("Failed to read the request: {err}")))?;
+    *head_size = head_size.saturating_add(u64::try_from(len).unwrap_or(u64::MAX));
+    if !line.ends_with('\n') {
+        return Err(if *head_size >= MAX_HEAD_SIZE {
+            HttpError::new(
+                StatusCode::RequestHeaderFieldsTooLarge,
+                "request-header-fields-too-large",
+                format!("The request line and headers are larger than {MAX_HEAD_SIZE} bytes"),
+            )
+        } else {
+            HttpError::bad_request("The request is incomplete".to_owned())
+        });
+    }
+    let content_len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(content_len);
+    Ok(line)
+}
+
+/// Reads a request,
+/// answering `Expect: 100-continue` on `output`.
+fn read_request(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    max_body_size: usize,
+) -> Result<Request, HttpError> {
+    let mut head_size = 0;
+    let request_line = read_head_line(input, &mut head_size)?;
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(target), Some(_version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(HttpError::bad_request(format!(
+            "Invalid request line: '{request_line}'"
+        )));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut content_length = None;
+    let mut content_type = None;
+    let mut transfer_encoding = false;
+    let mut expect_continue = false;
+    loop {
+        let line = read_head_line(input, &mut head_size)?;
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(HttpError::bad_request(format!("Invalid header: '{line}'")));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.parse::<usize>().map_err(|_| {
+                HttpError::bad_request(format!("Invalid Content-Length: '{value}'"))
+            })?);
+        } else if name.eq_ignore_ascii_case("content-type") {
+            content_type = value
+                .split(';')
+                .next()
+                .map(|media_type| media_type.trim().to_ascii_lowercase());
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            transfer_encoding = true;
+        } else if name.eq_ignore_ascii_case("expect") {
+            expect_continue = value.eq_ignore_ascii_case("100-continue");
+        }
+    }
+
+    let content_length = match content_length {
+        Some(content_length) if !transfer_encoding => content_length,
+        None if !transfer_encoding && method != "POST" => 0,
+        Some(_) | None => {
+            return Err(HttpError::new(
+                StatusCode::LengthRequired,
+                "length-required",
+                "Only request bodies with a Content-Length are supported".to_owned(),
+            ));
+        }
+    };
+    if content_length > max_body_size {
+        return Err(HttpError::new(
+            StatusCode::ContentTooLarge,
+            "content-too-large",
+            format!("The request body is larger than the maximum of {max_body_size} bytes"),
+        ));
+    }
+    if expect_continue && content_length > 0 {
+        output
+            .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+            .and_then(|()| output.flush())
+            .map_err(|err| HttpError::bad_request(format!("Failed to continue: {err}")))?;
+    }
+    let mut body = vec![0; content_length];
+    input
+        .read_exact(&mut body)
+        .map_err(|err| HttpError::bad_request(format!("Failed to read the request body: {err}")))?;
+
+    Ok(Request {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        query: query.to_owned(),
+        content_type,
+        body,
+    })
+}
+
+/// Collects the options given in the query of a request,
+/// like `?indentation=4&predicate-order=rdf:type,rdfs:label`.
+///
+/// Values are taken as JSON if possible, and as strings otherwise.
+/// An option without a value is taken as `true`.
+fn query_options(query: &str) -> Map<String, Value> {
+    // '+' stands for a space in form-encoded queries
+    let decode = |text: &str| encoding::percent_decode(&text.replace('+', " "));
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let Some((key, value)) = pair.split_once('=') else {
+                return (decode(pair), Value::Bool(true));
+            };
+            let key = decode(key);
+            let value = decode(value);
+            let value = if LIST_OPTIONS.contains(&key.as_str()) {
+                value
+                    .split(',')
+                    .filter(|item| !item.is_empty())
+                    .map(|item| Value::String(item.to_owned()))
+                    .collect()
+            } else {
+                serde_json::from_str(&value).unwrap_or(Value::String(value))
+            };
+            (key, value)
+        })
+        .collect()
+}
+
+/// Parses the formatting options given with a request,
+/// rejecting unknown ones.
+fn parse_options(options: Map<String, Value>) -> Result<PartialFormatOptions, HttpError> {
+    let known = serde_json::to_value(PartialFormatOptions::default()).unwrap_or_default();
+    let unknown: Vec<&str> = options
+        .keys()
+        .map(String::as_str)
+        .filter(|key| known.get(key).is_none())
+        .collect();
+    if !unknown.is_empty() {
+        return Err(HttpError::invalid_options(format!(
+            "Unknown options: {}",
+            unknown.join(", ")
+        )));
+    }
+    serde_json::from_value(Value::Object(options))
+        .map_err(|err| HttpError::invalid_options(format!("Invalid options: {err}")))
+}
+
+fn format(request: Request, base_options: &FormatOptions) -> Result<Response, HttpError> {
+    let mut options = base_options.clone();
+    parse_options(query_options(&request.query))?.apply_to(&mut options);
+    let content_type = request.content_type.as_deref();
+    let is_json = content_type == Some(MEDIA_TYPE_JSON);
+    let (text, input_format) = if is_json {
+        let format_request: FormatRequest = serde_json::from_slice(&request.body)
+            .map_err(|err| HttpError::bad_request(format!("Invalid JSON request: {err}")))?;
+        parse_options(format_request.options)?.apply_to(&mut options);
+        (format_request.text, format_request.format.into())
+    } else {
+        let text = encoding::decode(request.body).map_err(|offset| {
+            HttpError::new(
+                StatusCode::BadRequest,
+                "invalid-utf8",
+                format!("The request body is not valid UTF-8, starting at byte offset {offset}"),
+            )
+        })?;
+        let input_format = if content_type == Some(MEDIA_TYPE_TRIG) {
+            InputFormat::TriG
+        } else {
+            InputFormat::Turtle
+        };
+        (text, input_format)
+    };
+
+    let skipped = is_skipped(&text);
+    let formatted = if skipped {
+        text.clone()
+    } else {
+        format_str_styled(&text, input_format, &Arc::new(options))
+            .map_err(|err| HttpError::from(&err))?
+    };
+    Ok(if is_json {
+        Response::json(
+            StatusCode::Ok,
+            &json!({ "text": formatted, "changed": formatted != text, "skipped": skipped }),
+        )
+    } else {
+        Response {
+            status: StatusCode::Ok,
+            content_type: if input_format == InputFormat::TriG {
+                "application/trig; charset=utf-8"
+            } else {
+                "text/turtle; charset=utf-8"
+            },
+            allow: None,
+            body: formatted.into_bytes(),
+        }
+    })
+}
+
+fn respond(request: Request, base_options: &FormatOptions) -> Result<Response, HttpError> {
+    tracing::debug!("{} {}", request.method, request.path);
+    match (request.path.as_str(), request.method.as_str()) {
+        (PATH_HEALTH, "GET") => Ok(Response::json(
+            StatusCode::Ok,
+            &json!({ "status": "ok", "version": VERSION }),
+        )),
+        (PATH_FORMAT, "POST") => format(request, base_options),
+        (PATH_HEALTH, method) => Err(HttpError::method_not_allowed(method, "GET")),
+        (PATH_FORMAT, method) => Err(HttpError::method_not_allowed(method, "POST")),
+        (path, _) => Err(HttpError::new(
+            StatusCode::NotFound,
+            "not-found",
+            format!("No such path: '{path}'"),
+        )),
+    }
+}
+
+/// Answers the single request of a connection.
+fn handle(
+    stream: &TcpStream,
+    base_options: &FormatOptions,
+    max_body_size: usize,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut input = BufReader::new(stream);
+    let mut output = stream;
+    let response = read_request(&mut input, &mut output, max_body_size)
+        .and_then(|request| respond(request, base_options))
+        .unwrap_or_else(Response::from);
+    response.write_to(&mut output)?;
+    stream.shutdown(Shutdown::Write)
+}
+
+/// Serves formatting over HTTP on the connections accepted by `listener`,
+/// until the process gets stopped,
+/// with `base_options` overridden by the options of each request.
+///
+/// A fixed number of connections is answered concurrently;
+/// further ones wait in a queue of limited size,
+/// and once that is full, no more connections get accepted
+/// until there is room again.
+pub fn serve(listener: &TcpListener, base_options: &Arc<FormatOptions>, max_body_size: usize) {
+    let (sender, receiver) = mpsc::sync_channel::<TcpStream>(QUEUE_SIZE);
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..WORKERS {
+        let receiver = Arc::clone(&receiver);
+        let base_options = Arc::clone(base_options);
+        thread::spawn(move || {
+            loop {
+                // The lock is only held while waiting for the next connection
+                let next = receiver
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .recv();
+                let Ok(stream) = next else {
+                    break;
+                };
+                if let Err(err) = handle(&stream, &base_options, max_body_size) {
+                    tracing::debug!("Failed to answer a request: {err}");
+                }
+            }
+        });
+    }
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if sender.send(stream).is_err() {
+                    tracing::error!("All workers stopped");
+                    break;
+                }
+            }
+            Err(err) => tracing::warn!("Failed to accept a connection: {err}"),
+        }
+    }
+}
+
+/// Serves formatting over HTTP on the address `listen`,
+/// until the process gets stopped.
+///
+/// The formatting options are resolved once,
+/// the same way as for content from stdin,
+/// and each request can override them.
+/// See [`serve`].
+///
+/// # Errors
+///
+/// - [`Error::Config`] if a configuration file is invalid
+/// - [`Error::FailedToListen`] if listening on `listen` fails
+pub fn run(resolver: &mut Resolver, listen: &str, max_body_size: usize) -> Result<(), Error> {
+    let base_options = Arc::new(resolver.resolve(Path::new(STDIN_SOURCE))?);
+    let listener =
+        TcpListener::bind(listen).map_err(|err| Error::FailedToListen(err, listen.to_owned()))?;
+    let local_addr = listener
+        .local_addr()
+        .map_or_else(|_| listen.to_owned(), |addr| addr.to_string());
+    tracing::info!("Listening on http://{local_addr}");
+    serve(&listener, &base_options, max_body_size);
+    Ok(())
+}
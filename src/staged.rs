@@ -0,0 +1,292 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Formatting the files staged in git,
+//! as done in a pre-commit hook.
+//!
+//! The staged content (not the working copy) is formatted,
+//! and written back to the index.
+//! The working copy is only updated if it has no changes
+//! on top of the staged content,
+//! so unstaged changes are never touched.
+//!
+//! Only the local repository is accessed,
+//! through the `git` command.
+
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use crate::config::Resolver;
+use crate::error::Error;
+use crate::options::RunOptions;
+use crate::parser::InputFormat;
+use crate::report::{FileReport, Report, Status};
+use crate::{check_status, encoding, format_str_styled, input_format, is_skipped, write};
+
+const GIT: &str = "git";
+
+/// The file modes of regular files in the git index.
+const REGULAR_FILE_MODES: [&str; 2] = ["100644", "100755"];
+
+/// Runs git in `dir`, optionally feeding it `stdin`,
+/// and returns what it writes to stdout.
+///
+/// # Errors
+///
+/// - [`Error::FailedToRunGit`] if git can not be run
+/// - [`Error::GitCommandFailed`] if it exits unsuccessfully
+fn git<I, S>(dir: &Path, args: I, stdin: Option<&[u8]>) -> Result<Vec<u8>, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args: Vec<OsString> = args
+        .into_iter()
+        .map(|arg| arg.as_ref().to_owned())
+        .collect();
+    let command_line = || {
+        std::iter::once(GIT.into())
+            .chain(args.iter().map(|arg| arg.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut child = Command::new(GIT)
+        .arg("-C")
+        .arg(dir)
+        .args(&args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| Error::FailedToRunGit(err, command_line()))?;
+    if let (Some(input), Some(mut child_stdin)) = (stdin, child.stdin.take()) {
+        child_stdin
+            .write_all(input)
+            .map_err(|err| Error::FailedToRunGit(err, command_line()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|err| Error::FailedToRunGit(err, command_line()))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(Error::GitCommandFailed(
+            command_line(),
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ))
+    }
+}
+
+/// Like [`git`], but returns stdout as a trimmed string.
+fn git_str<I, S>(dir: &Path, args: I) -> Result<String, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    Ok(String::from_utf8_lossy(&git(dir, args, None)?)
+        .trim()
+        .to_owned())
+}
+
+/// A file in the git index.
+struct StagedFile {
+    /// The path relative to the root of the repository.
+    path: PathBuf,
+    mode: String,
+    /// The object ID of the staged content.
+    blob: String,
+}
+
+/// Lists the staged files of a format we format,
+/// restricted to those matching one of the `pathspecs`, if any.
+fn staged_files(root: &Path, pathspecs: &[PathBuf]) -> Result<Vec<StagedFile>, Error> {
+    let cwd = Path::new(".");
+    let mut diff_args: Vec<OsString> = [
+        "diff",
+        "--cached",
+        "--name-only",
+        "-z",
+        "--diff-filter=ACMR",
+        "--",
+    ]
+    .into_iter()
+    .map(OsString::from)
+    .collect();
+    diff_args.extend(
+        pathspecs
+            .iter()
+            .map(|pathspec| pathspec.as_os_str().to_owned()),
+    );
+    let names = git(cwd, diff_args, None)?;
+
+    let mut files = Vec::new();
+    for name in names
+        .split(|byte| *byte == 0)
+        .filter(|name| !name.is_empty())
+    {
+        let path = PathBuf::from(String::from_utf8_lossy(name).into_owned());
+        if !InputFormat::from_path(&path)
+            .is_some_and(|format| InputFormat::FORMATTABLE.contains(&format))
+        {
+            continue;
+        }
+        let entry = git_str(
+            root,
+            [
+                OsStr::new("ls-files"),
+                OsStr::new("-s"),
+                OsStr::new("--"),
+                path.as_os_str(),
+            ],
+        )?;
+        // Format: "<mode> <object> <stage>\t<path>"
+        let mut fields = entry
+            .split_once('\t')
+            .map(|(info, _path)| info)
+            .unwrap_or_default()
+            .split_whitespace();
+        let (Some(mode), Some(blob)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if !REGULAR_FILE_MODES.contains(&mode) {
+            tracing::info!("Skipping '{}', as it is not a regular file", path.display());
+            continue;
+        }
+        files.push(StagedFile {
+            path,
+            mode: mode.to_owned(),
+            blob: blob.to_owned(),
+        });
+    }
+    Ok(files)
+}
+
+/// The object ID the working copy of a file would get if it was staged,
+/// or `None` if it can not be read.
+///
+/// This applies the same filters as staging does (e.g. for line endings),
+/// so it matches the staged object ID
+/// exactly if the working copy has no unstaged changes.
+fn work_blob(root: &Path, staged_file: &StagedFile) -> Option<String> {
+    let mut path_arg = OsString::from("--path=");
+    path_arg.push(staged_file.path.as_os_str());
+    git_str(
+        root,
+        [
+            OsStr::new("hash-object"),
+            path_arg.as_os_str(),
+            OsStr::new("--"),
+            root.join(&staged_file.path).as_os_str(),
+        ],
+    )
+    .ok()
+}
+
+/// Formats the staged content of a single file,
+/// writing it back to the index,
+/// and to the working copy if that has no unstaged changes.
+fn process_staged_file(
+    resolver: &mut Resolver,
+    run_options: &RunOptions,
+    root: &Path,
+    staged_file: &StagedFile,
+) -> Result<Status, Error> {
+    let work_path = root.join(&staged_file.path);
+    let options = Arc::new(resolver.resolve(&work_path)?);
+    let staged_bytes = git(root, ["cat-file", "blob", &staged_file.blob], None)?;
+    let original = encoding::decode(staged_bytes)
+        .map_err(|offset| Error::InvalidUtf8(staged_file.path.clone(), offset))?;
+    if is_skipped(&original) {
+        tracing::debug!(
+            "Skipping '{}', as formatting is turned off for it",
+            staged_file.path.display()
+        );
+        return Ok(Status::Skipped);
+    }
+    let formatted = format_str_styled(
+        &original,
+        input_format(&staged_file.path, run_options)?,
+        &options,
+    )?;
+    if options.check {
+        return Ok(check_status(&original, &formatted));
+    }
+    if original == formatted {
+        return Ok(Status::Unchanged);
+    }
+
+    // The content is in its staged form already, so no filters (e.g. for line endings) apply
+    let blob = String::from_utf8_lossy(&git(
+        root,
+        [
+            OsStr::new("hash-object"),
+            OsStr::new("-w"),
+            OsStr::new("--stdin"),
+            OsStr::new("--no-filters"),
+        ],
+        Some(formatted.as_bytes()),
+    )?)
+    .trim()
+    .to_owned();
+    let mut cache_info = OsString::from(format!("{},{blob},", staged_file.mode));
+    cache_info.push(staged_file.path.as_os_str());
+    git(
+        root,
+        [
+            OsStr::new("update-index"),
+            OsStr::new("--cacheinfo"),
+            cache_info.as_os_str(),
+        ],
+        None,
+    )?;
+
+    if work_blob(root, staged_file).is_some_and(|work_blob| work_blob == staged_file.blob) {
+        write::write_file(&work_path, &formatted, run_options.backup_suffix.as_deref())?;
+    } else {
+        tracing::info!(
+            "Formatted the staged content of '{}', \
+but left the working copy as is, because it has unstaged changes",
+            staged_file.path.display()
+        );
+    }
+    Ok(Status::Formatted)
+}
+
+/// Formats the Turtle and TriG files staged in the git repository
+/// of the current working directory,
+/// restricted to those matching one of the `pathspecs`, if any.
+///
+/// In check mode, nothing is written;
+/// the result is only reported.
+/// A failure to process one file does not stop the processing of the others;
+/// it is reported as [`Status::Error`] instead.
+///
+/// # Errors
+///
+/// - [`Error::FailedToRunGit`] and [`Error::GitCommandFailed`]
+///   if the staged files can not be listed,
+///   for example because we are not within a git repository
+pub fn run(
+    resolver: &mut Resolver,
+    run_options: &RunOptions,
+    pathspecs: &[PathBuf],
+) -> Result<Report, Error> {
+    let root = PathBuf::from(git_str(Path::new("."), ["rev-parse", "--show-toplevel"])?);
+    let files = staged_files(&root, pathspecs)?
+        .iter()
+        .map(|staged_file| FileReport {
+            path: staged_file.path.clone(),
+            status: process_staged_file(resolver, run_options, &root, staged_file)
+                .unwrap_or_else(Status::Error),
+        })
+        .collect();
+    Ok(Report { files })
+}
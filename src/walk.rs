@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Finding the files to format within a source directory.
+//!
+//! Files and directories matched by `.gitignore` files
+//! (also outside of git repositories),
+//! by [`IGNORE_FILE_NAME`] files
+//! or by [`RunOptions::exclude`] are skipped,
+//! as are hidden files and directories (like `.git`).
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+use ignore::overrides::{Override, OverrideBuilder};
+
+use crate::error::Error;
+use crate::options::RunOptions;
+
+/// The name of the files containing gitignore-style patterns
+/// of files and directories that should not be formatted.
+pub const IGNORE_FILE_NAME: &str = ".prttlignore";
+
+/// Whether the error is (or wraps) a file system loop,
+/// found while following symbolic links.
+fn is_loop(err: &ignore::Error) -> bool {
+    match err {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithLineNumber { err, .. }
+        | ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. } => is_loop(err),
+        ignore::Error::Partial(_)
+        | ignore::Error::Io(_)
+        | ignore::Error::Glob { .. }
+        | ignore::Error::UnrecognizedFileType(_)
+        | ignore::Error::InvalidDefinition => false,
+    }
+}
+
+/// Creates the matcher for [`RunOptions::exclude`],
+/// with globs relative to `dir`.
+fn build_excludes(dir: &Path, run_options: &RunOptions) -> Result<Override, Error> {
+    let mut overrides = OverrideBuilder::new(dir);
+    for glob in &run_options.exclude {
+        overrides
+            .add(&format!("!{glob}"))
+            .map_err(|err| Error::InvalidGlob(Box::new(err)))?;
+    }
+    overrides
+        .build()
+        .map_err(|err| Error::InvalidGlob(Box::new(err)))
+}
+
+/// Creates the matcher for [`RunOptions::include`],
+/// with globs relative to `dir`.
+///
+/// This is not handed to the walker as overrides,
+/// because there, matching files would bypass the ignore files
+/// and the filtering of hidden files.
+fn build_includes(dir: &Path, run_options: &RunOptions) -> Result<Override, Error> {
+    let mut overrides = OverrideBuilder::new(dir);
+    for glob in &run_options.include {
+        overrides
+            .add(glob)
+            .map_err(|err| Error::InvalidGlob(Box::new(err)))?;
+    }
+    overrides
+        .build()
+        .map_err(|err| Error::InvalidGlob(Box::new(err)))
+}
+
+/// Recursively finds the files with one of the given extensions within a directory,
+/// sorted by path.
+///
+/// Files matching [`RunOptions::include`] are still subject
+/// to the ignore files and the filtering of hidden files.
+///
+/// Symbolic links are only followed if [`RunOptions::follow_symlinks`] is set;
+/// otherwise they are skipped (with a note in the log).
+/// File system loops created by symbolic links are detected, logged and skipped.
+///
+/// # Errors
+///
+/// - [`Error::InvalidGlob`] if one of the include or exclude globs is invalid
+/// - [`Error::FailedToWalkInputDir`] if the directory (or a sub-directory) can not be read
+pub fn find_files(
+    dir: &Path,
+    extensions: &[&str],
+    run_options: &RunOptions,
+) -> Result<Vec<PathBuf>, Error> {
+    let walker = WalkBuilder::new(dir)
+        .add_custom_ignore_filename(IGNORE_FILE_NAME)
+        .require_git(false)
+        .follow_links(run_options.follow_symlinks)
+        .overrides(build_excludes(dir, run_options)?)
+        .build();
+    let includes = build_includes(dir, run_options)?;
+
+    let mut files = Vec::new();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) if is_loop(&err) => {
+                tracing::warn!("Skipping file system loop: {err}");
+                continue;
+            }
+            Err(err) => {
+                return Err(Error::FailedToWalkInputDir(
+                    Box::new(err),
+                    dir.to_path_buf(),
+                ));
+            }
+        };
+        if let Some(err) = entry.error() {
+            tracing::warn!("Problem with an ignore file: {err}");
+        }
+        let Some(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            tracing::info!(
+                "Skipping symbolic link '{}'; use --follow-symlinks to follow it",
+                entry.path().display()
+            );
+        } else if file_type.is_file()
+            && entry
+                .path()
+                .extension()
+                .and_then(OsStr::to_str)
+                .is_some_and(|extension| extensions.contains(&extension))
+            && (includes.is_empty() || includes.matched(entry.path(), false).is_whitelist())
+        {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
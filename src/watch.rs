@@ -0,0 +1,198 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-formatting files whenever they change.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify_debouncer_mini::new_debouncer;
+use notify_debouncer_mini::notify::{self, RecursiveMode, Watcher};
+
+use crate::config::{self, Resolver};
+use crate::error::Error;
+use crate::options::RunOptions;
+use crate::report::Report;
+use crate::{InputFile, collect_input_files, run};
+
+/// How long to wait for further changes,
+/// before formatting the changed files.
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// The hash of the content of a file, if it can be read.
+fn content_hash(path: &Path) -> Option<blake3::Hash> {
+    fs::read(path).ok().map(|content| blake3::hash(&content))
+}
+
+/// Identifies a file independent of how its path was given.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The content with which we left the files (and their outputs)
+/// after formatting them,
+/// which allows to ignore the changes we caused ourselves.
+///
+/// We compare the content rather than modification times,
+/// as the latter are too coarse on some file systems
+/// to tell our writes apart from those made right after them.
+#[derive(Default)]
+struct OwnWrites {
+    hashes: HashMap<PathBuf, blake3::Hash>,
+}
+
+impl OwnWrites {
+    fn remember(&mut self, files: &[InputFile], run_options: &RunOptions) {
+        for file in files {
+            let output = file.output_path(run_options);
+            for path in std::iter::once(&file.path).chain(output.as_ref()) {
+                if let Some(hash) = content_hash(path) {
+                    self.hashes.insert(canonical(path), hash);
+                }
+            }
+        }
+    }
+
+    fn is_own(&self, path: &Path) -> bool {
+        self.hashes
+            .get(&canonical(path))
+            .is_some_and(|own_hash| Some(*own_hash) == content_hash(path))
+    }
+}
+
+/// What to watch for a source:
+/// Directories recursively,
+/// and files through their parent directory,
+/// as editors commonly replace a file when saving it.
+fn watch_target(source: &Path) -> (&Path, RecursiveMode) {
+    if source.is_dir() {
+        (source, RecursiveMode::Recursive)
+    } else {
+        let parent = source
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        (parent, RecursiveMode::NonRecursive)
+    }
+}
+
+/// Watches the directories of the configuration files loaded so far,
+/// which may lie outside of the sources,
+/// unless they are watched already.
+fn watch_configs(
+    watcher: &mut dyn Watcher,
+    resolver: &Resolver,
+    watched_dirs: &mut HashSet<PathBuf>,
+) -> Result<(), notify::Error> {
+    for file in resolver.config_files() {
+        let (dir, recursive_mode) = watch_target(&file);
+        if !watched_dirs.contains(dir) {
+            watcher.watch(dir, recursive_mode)?;
+            watched_dirs.insert(dir.to_owned());
+        }
+    }
+    Ok(())
+}
+
+/// Runs the formatter on the files found in `src`,
+/// as [`run`] does,
+/// and then keeps watching `src`,
+/// running the formatter again on each file that changes,
+/// until the process is terminated.
+///
+/// The report of each run is handed to `on_report`.
+/// Files that fail to be processed are reported as [`crate::report::Status::Error`],
+/// and do not stop the watching.
+/// The changes caused by writing the formatted content are ignored.
+/// When a configuration file changes,
+/// all files are formatted again, with the changed options.
+/// This includes the configuration files above the sources
+/// that were used for formatting them,
+/// but not those created after they were last formatted.
+///
+/// # Errors
+///
+/// - any error from [`collect_input_files`] and [`run`] for the initial run
+/// - [`Error::FailedToWatch`] if watching one of the sources fails
+pub fn watch(
+    resolver: &mut Resolver,
+    run_options: &RunOptions,
+    src: &[PathBuf],
+    extensions: &[&str],
+    mut on_report: impl FnMut(&Report),
+) -> Result<(), Error> {
+    let mut own_writes = OwnWrites::default();
+    let files = collect_input_files(src, extensions, run_options)?;
+    let report = run(resolver, run_options, &files)?;
+    own_writes.remember(&files, run_options);
+    on_report(&report);
+
+    let (sender, receiver) = mpsc::channel();
+    let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, sender)
+        .map_err(|err| Error::FailedToWatch(Box::new(err)))?;
+    for source in src {
+        let (path, recursive_mode) = watch_target(source);
+        debouncer
+            .watcher()
+            .watch(path, recursive_mode)
+            .map_err(|err| Error::FailedToWatch(Box::new(err)))?;
+    }
+    let mut watched_config_dirs = HashSet::new();
+    watch_configs(debouncer.watcher(), resolver, &mut watched_config_dirs)
+        .map_err(|err| Error::FailedToWatch(Box::new(err)))?;
+    tracing::info!("Watching for changes ...");
+
+    for events_res in receiver {
+        let changed: HashSet<PathBuf> = match events_res {
+            Ok(events) => events
+                .into_iter()
+                .map(|event| canonical(&event.path))
+                .collect(),
+            Err(err) => {
+                tracing::warn!("Problem while watching for changes: {err}");
+                continue;
+            }
+        };
+        let config_changed = changed
+            .iter()
+            .any(|path| path.file_name() == Some(OsStr::new(config::FILE_NAME)));
+        if config_changed {
+            resolver.clear_cache();
+        }
+        // We collect the files anew, to pick up newly created ones,
+        // and to respect changes to ignore files.
+        let files = match collect_input_files(src, extensions, run_options) {
+            Ok(files) => files,
+            Err(err) => {
+                tracing::error!("{err}");
+                continue;
+            }
+        };
+        let files: Vec<InputFile> = files
+            .into_iter()
+            .filter(|file| {
+                config_changed
+                    || (changed.contains(&canonical(&file.path)) && !own_writes.is_own(&file.path))
+            })
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        match run(resolver, run_options, &files) {
+            Ok(report) => {
+                own_writes.remember(&files, run_options);
+                on_report(&report);
+            }
+            Err(err) => tracing::error!("{err}"),
+        }
+        if let Err(err) = watch_configs(debouncer.watcher(), resolver, &mut watched_config_dirs) {
+            tracing::warn!("Problem while watching configuration files: {err}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Writing formatted content to files,
+//! without ever leaving them half-written.
+
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, WriteStep};
+
+/// The prefix of the temporary files we write to.
+const TEMP_FILE_PREFIX: &str = ".prttl-";
+
+/// The mode of files we create, before applying the umask.
+#[cfg(unix)]
+const NEW_FILE_MODE: u32 = 0o666;
+
+/// The path of the backup file for the given file.
+#[must_use]
+pub fn backup_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut backup: OsString = path.as_os_str().to_owned();
+    backup.push(suffix);
+    PathBuf::from(backup)
+}
+
+/// Writes content to a file atomically:
+/// The content is written to a temporary file in the same directory first,
+/// which is then renamed to the target file.
+///
+/// If the target file exists already,
+/// its permissions are kept,
+/// otherwise it gets the permissions a newly created file would get
+/// (on Unix: `0o666` minus the umask),
+/// and - if a `backup_suffix` is given -
+/// its previous content is copied to a file with that suffix appended.
+/// If the target file is a symbolic link,
+/// the file it points to is replaced.
+///
+/// # Errors
+///
+/// [`Error::FailedToWriteFormattedFile`] with the step that failed
+pub fn write_file(path: &Path, content: &str, backup_suffix: Option<&str>) -> Result<(), Error> {
+    let failed = |step: WriteStep| {
+        let path = path.to_path_buf();
+        move |err: io::Error| Error::FailedToWriteFormattedFile(err, path, step)
+    };
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = target
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+
+    let existing = fs::metadata(&target).ok();
+
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(TEMP_FILE_PREFIX);
+    #[cfg(unix)]
+    if existing.is_none() {
+        use std::os::unix::fs::PermissionsExt;
+        // Instead of the `0o600` of temporary files;
+        // the umask gets applied on creation, as with `File::create`
+        builder.permissions(fs::Permissions::from_mode(NEW_FILE_MODE));
+    }
+    let mut temp_file = builder
+        .tempfile_in(dir)
+        .map_err(failed(WriteStep::CreateTemporary))?;
+    temp_file
+        .write_all(content.as_bytes())
+        .and_then(|()| temp_file.as_file().sync_all())
+        .map_err(failed(WriteStep::Write))?;
+
+    if let Some(metadata) = existing {
+        temp_file
+            .as_file()
+            .set_permissions(metadata.permissions())
+            .map_err(failed(WriteStep::CopyPermissions))?;
+        if let Some(suffix) = backup_suffix {
+            fs::copy(&target, backup_path(&target, suffix)).map_err(failed(WriteStep::Backup))?;
+        }
+    }
+
+    temp_file
+        .persist(&target)
+        .map_err(|err| failed(WriteStep::Rename)(err.error))?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+
+use prttl::cache::Cache;
+use prttl::config::Resolver;
+use prttl::options::{FormatOptions, PartialFormatOptions, RunOptions};
+use prttl::parser::InputFormat;
+use prttl::report::Status;
+use prttl::{InputFile, VERSION};
+
+const FORMATTED: &str = "<http://example.org/s> <http://example.org/p> <http://example.org/o> .\n";
+
+/// Not valid Turtle, so formatting it would fail.
+const INVALID: &str = "<http://example.org/s> <http://example.org/p> .\n";
+
+fn options() -> FormatOptions {
+    FormatOptions {
+        check: false,
+        ..FormatOptions::default()
+    }
+}
+
+fn run(cache_dir: &Path, file: &Path) -> Status {
+    let run_options = RunOptions {
+        cache_dir: Some(cache_dir.to_owned()),
+        ..RunOptions::default()
+    };
+    let input_file = InputFile {
+        path: file.to_owned(),
+        relative: file.file_name().unwrap().into(),
+    };
+    let mut report = prttl::run(
+        &mut Resolver::new(options(), PartialFormatOptions::default()),
+        &run_options,
+        &[input_file],
+    )
+    .unwrap();
+    report.files.remove(0).status
+}
+
+#[test]
+fn hit_skips_formatting() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_dir = dir.path().join("cache");
+    let file = dir.path().join("a.ttl");
+    fs::write(&file, INVALID).unwrap();
+    assert!(matches!(run(&cache_dir, &file), Status::Error(_)));
+
+    Cache::new(cache_dir.clone()).insert(INVALID, InputFormat::Turtle, &options());
+    assert!(matches!(run(&cache_dir, &file), Status::Unchanged));
+}
+
+#[test]
+fn formatted_content_is_recorded() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_dir = dir.path().join("cache");
+    let file = dir.path().join("a.ttl");
+    let cache = Cache::new(cache_dir.clone());
+    let formatted = prttl::format_str(FORMATTED, &options().into()).unwrap();
+    fs::write(&file, &formatted).unwrap();
+
+    assert!(!cache.is_formatted(&formatted, InputFormat::Turtle, &options()));
+    assert!(matches!(run(&cache_dir, &file), Status::Unchanged));
+    assert!(cache.is_formatted(&formatted, InputFormat::Turtle, &options()));
+    assert!(cache_dir.join(".gitignore").is_file());
+}
+
+#[test]
+fn changed_options_invalidate() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path().to_owned());
+    cache.insert(FORMATTED, InputFormat::Turtle, &options());
+
+    assert!(cache.is_formatted(FORMATTED, InputFormat::Turtle, &options()));
+    assert!(!cache.is_formatted(FORMATTED, InputFormat::TriG, &options()));
+    let changed = FormatOptions {
+        indentation: "    ".to_owned(),
+        ..options()
+    };
+    assert!(!cache.is_formatted(FORMATTED, InputFormat::Turtle, &changed));
+}
+
+#[test]
+fn changed_version_invalidates() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = Cache::new(dir.path().to_owned());
+    cache.insert(FORMATTED, InputFormat::Turtle, &options());
+    assert!(cache.is_formatted(FORMATTED, InputFormat::Turtle, &options()));
+
+    // As if the entry was recorded by a different version
+    fs::rename(dir.path().join(VERSION), dir.path().join("0.0.0")).unwrap();
+    assert!(!cache.is_formatted(FORMATTED, InputFormat::Turtle, &options()));
+}
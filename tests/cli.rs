@@ -0,0 +1,278 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
+use pretty_assertions::assert_eq;
+
+const UNFORMATTED: &str = "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n";
+
+const FORMATTED: &str = "PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n";
+
+fn prttl(dir: &Path) -> Command {
+    let mut cmd = cargo_bin_cmd!("prttl");
+    cmd.current_dir(dir);
+    cmd
+}
+
+fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap()
+}
+
+fn stdout(cmd: &mut Command) -> String {
+    String::from_utf8(cmd.output().unwrap().stdout).unwrap()
+}
+
+/// The names of the files in a directory, sorted.
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+#[test]
+fn formats_stdin_to_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    prttl(dir.path())
+        .arg("-")
+        .write_stdin(UNFORMATTED)
+        .assert()
+        .success()
+        .stdout(FORMATTED);
+}
+
+#[test]
+fn stdin_filepath_selects_the_configuration() {
+    let dir = tempfile::tempdir().unwrap();
+    write(
+        &dir.path().join("sub/.prttl.toml"),
+        "root = true\nsparql-syntax = false\n",
+    );
+    prttl(dir.path())
+        .args(["--stdin-filepath", "sub/a.ttl", "-"])
+        .write_stdin(UNFORMATTED)
+        .assert()
+        .success()
+        .stdout("@prefix ex: <http://example.org/> .\n\nex:s ex:p ex:o .\n");
+}
+
+#[test]
+fn writes_to_the_output_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("in.ttl");
+    let output = dir.path().join("out.ttl");
+    write(&input, UNFORMATTED);
+
+    prttl(dir.path())
+        .args(["in.ttl", "--output", "out.ttl"])
+        .assert()
+        .success();
+    assert_eq!(read(&input), UNFORMATTED);
+    assert_eq!(read(&output), FORMATTED);
+
+    // An existing output is only overwritten when forced to
+    write(&output, "");
+    prttl(dir.path())
+        .args(["in.ttl", "--output", "out.ttl"])
+        .assert()
+        .failure();
+    assert_eq!(read(&output), "");
+    prttl(dir.path())
+        .args(["in.ttl", "--output", "out.ttl", "--force"])
+        .assert()
+        .success();
+    assert_eq!(read(&output), FORMATTED);
+}
+
+#[test]
+fn writes_stdin_to_the_output_file() {
+    let dir = tempfile::tempdir().unwrap();
+    prttl(dir.path())
+        .args(["-", "--output", "out.ttl"])
+        .write_stdin(UNFORMATTED)
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(read(&dir.path().join("out.ttl")), FORMATTED);
+}
+
+#[test]
+fn mirrors_the_sources_in_the_out_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    write(&dir.path().join("src/a.ttl"), UNFORMATTED);
+    write(&dir.path().join("src/sub/b.ttl"), UNFORMATTED);
+
+    prttl(dir.path())
+        .args(["src", "--out-dir", "out"])
+        .assert()
+        .success();
+    assert_eq!(read(&dir.path().join("out/a.ttl")), FORMATTED);
+    assert_eq!(read(&dir.path().join("out/sub/b.ttl")), FORMATTED);
+    assert_eq!(read(&dir.path().join("src/sub/b.ttl")), UNFORMATTED);
+}
+
+#[cfg(unix)]
+#[test]
+fn new_outputs_get_the_default_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    let dir = tempfile::tempdir().unwrap();
+    write(&dir.path().join("src/a.ttl"), UNFORMATTED);
+    // What a plain create gives, with the umask of this process applied
+    let plain = dir.path().join("plain");
+    fs::File::create(&plain).unwrap();
+
+    prttl(dir.path())
+        .args(["src/a.ttl", "--output", "single.ttl"])
+        .assert()
+        .success();
+    prttl(dir.path())
+        .args(["src", "--out-dir", "out"])
+        .assert()
+        .success();
+    assert_eq!(mode(&dir.path().join("single.ttl")), mode(&plain));
+    assert_eq!(mode(&dir.path().join("out/a.ttl")), mode(&plain));
+}
+
+#[test]
+fn check_reports_every_unformatted_file() {
+    let dir = tempfile::tempdir().unwrap();
+    write(&dir.path().join("d/a.ttl"), UNFORMATTED);
+    write(&dir.path().join("d/ok.ttl"), FORMATTED);
+    write(&dir.path().join("d/sub/b.ttl"), UNFORMATTED);
+
+    let output = prttl(dir.path())
+        .args(["--check", "--color", "never", "d"])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("d/a.ttl\n--- original\n+++ modified\n"));
+    assert!(stdout.contains("\nd/sub/b.ttl\n--- original\n"));
+    assert!(stdout.contains("\n+PREFIX ex: <http://example.org/>\n"));
+    assert!(!stdout.contains("ok.ttl"));
+    // Nothing is written
+    assert_eq!(read(&dir.path().join("d/a.ttl")), UNFORMATTED);
+
+    prttl(dir.path())
+        .args(["--list-different", "d"])
+        .assert()
+        .failure()
+        .stdout("d/a.ttl\nd/sub/b.ttl\n");
+    prttl(dir.path())
+        .args(["--list-different", "d/ok.ttl"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[test]
+fn jobs_keep_the_order() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut expected = String::new();
+    for index in 0..32 {
+        let name = format!("d/{index:02}.ttl");
+        write(&dir.path().join(&name), UNFORMATTED);
+        expected.push_str(&name);
+        expected.push('\n');
+    }
+
+    for jobs in ["1", "4", "32"] {
+        assert_eq!(
+            stdout(prttl(dir.path()).args(["--list-different", "--jobs", jobs, "d"])),
+            expected,
+            "with {jobs} jobs"
+        );
+    }
+}
+
+#[test]
+fn writes_atomically_with_backups() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("d/a.ttl");
+    write(&file, UNFORMATTED);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+    }
+
+    prttl(dir.path()).args(["--backup", "d"]).assert().success();
+    assert_eq!(read(&file), FORMATTED);
+    assert_eq!(read(&dir.path().join("d/a.ttl~")), UNFORMATTED);
+    // No temporary files are left behind
+    assert_eq!(file_names(&dir.path().join("d")), ["a.ttl", "a.ttl~"]);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    write(&file, UNFORMATTED);
+    prttl(dir.path())
+        .args(["--backup=.orig", "d/a.ttl"])
+        .assert()
+        .success();
+    assert_eq!(read(&dir.path().join("d/a.ttl.orig")), UNFORMATTED);
+}
+
+#[test]
+fn caches_by_default() {
+    let dir = tempfile::tempdir().unwrap();
+    write(&dir.path().join("d/a.ttl"), FORMATTED);
+
+    prttl(dir.path()).args(["--no-cache", "d"]).assert().success();
+    assert_eq!(file_names(dir.path()), ["d"]);
+
+    prttl(dir.path())
+        .args(["--cache=my-cache", "d"])
+        .assert()
+        .success();
+    assert_eq!(file_names(dir.path()), ["d", "my-cache"]);
+
+    prttl(dir.path()).arg("d").assert().success();
+    assert_eq!(file_names(dir.path()), [".prttl-cache", "d", "my-cache"]);
+
+    prttl(dir.path())
+        .args(["--cache=my-cache", "--no-cache", "d"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn prints_completions() {
+    let dir = tempfile::tempdir().unwrap();
+    for shell in ["bash", "fish", "zsh"] {
+        let script = stdout(prttl(dir.path()).args(["completions", shell]));
+        assert!(script.contains("list-different"), "{shell}");
+    }
+    prttl(dir.path())
+        .args(["completions", "no-shell"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn prints_the_man_page() {
+    let dir = tempfile::tempdir().unwrap();
+    let page = stdout(prttl(dir.path()).arg("man"));
+    assert!(page.contains(".TH prttl 1"));
+    assert!(page.contains("list\\-different"));
+}
@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+
+use pretty_assertions::assert_eq;
+use prttl::config::{self, FILE_NAME, Resolver};
+use prttl::options::{FormatOptions, PartialFormatOptions, SpecialPredicateOrder};
+
+fn write_config(dir: &Path, content: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join(FILE_NAME), content).unwrap();
+}
+
+fn resolver() -> Resolver {
+    Resolver::new(FormatOptions::default(), PartialFormatOptions::default())
+}
+
+#[test]
+fn closer_configs_take_precedence() {
+    let dir = tempfile::tempdir().unwrap();
+    write_config(
+        dir.path(),
+        "root = true\nindentation = 4\nsparql-syntax = true\n",
+    );
+    write_config(&dir.path().join("sub"), "indentation = 3\n");
+
+    let mut resolver = resolver();
+    let options = resolver.resolve(&dir.path().join("sub/a.ttl")).unwrap();
+    assert_eq!(options.indentation, "   ");
+    assert!(options.sparql_syntax);
+
+    let options = resolver.resolve(&dir.path().join("a.ttl")).unwrap();
+    assert_eq!(options.indentation, "    ");
+}
+
+#[test]
+fn root_stops_the_search() {
+    let dir = tempfile::tempdir().unwrap();
+    write_config(dir.path(), "root = true\nsparql-syntax = true\n");
+    write_config(&dir.path().join("sub"), "root = true\nindentation = 3\n");
+
+    let options = resolver().resolve(&dir.path().join("sub/a.ttl")).unwrap();
+    assert_eq!(options.indentation, "   ");
+    assert!(!options.sparql_syntax);
+}
+
+#[test]
+fn explicit_options_take_precedence() {
+    let dir = tempfile::tempdir().unwrap();
+    write_config(dir.path(), "root = true\nindentation = 4\n");
+
+    let explicit = PartialFormatOptions {
+        indentation: Some(1),
+        ..PartialFormatOptions::default()
+    };
+    let options = Resolver::new(FormatOptions::default(), explicit)
+        .resolve(&dir.path().join("a.ttl"))
+        .unwrap();
+    assert_eq!(options.indentation, " ");
+}
+
+#[test]
+fn overrides_match_relative_to_the_config() {
+    let dir = tempfile::tempdir().unwrap();
+    write_config(
+        dir.path(),
+        r#"root = true
+predicate-order-preset = "owl"
+
+[[overrides]]
+files = ["vocabularies/**/*.ttl"]
+predicate-order-preset = "skos"
+"#,
+    );
+
+    let mut resolver = resolver();
+    let options = resolver
+        .resolve(&dir.path().join("vocabularies/a/b.ttl"))
+        .unwrap();
+    assert!(matches!(
+        options.predicate_order_preset,
+        Some(SpecialPredicateOrder::Skos)
+    ));
+
+    let options = resolver.resolve(&dir.path().join("other/b.ttl")).unwrap();
+    assert!(matches!(
+        options.predicate_order_preset,
+        Some(SpecialPredicateOrder::Owl)
+    ));
+}
+
+#[test]
+fn order_presets_and_lists_replace_each_other() {
+    let dir = tempfile::tempdir().unwrap();
+    write_config(
+        dir.path(),
+        r#"root = true
+predicate-order = ["ex:p"]
+subject-type-order-preset = "owl"
+
+[[overrides]]
+files = ["vocabularies/*.ttl"]
+predicate-order-preset = "skos"
+subject-type-order = ["ex:T"]
+"#,
+    );
+    write_config(
+        &dir.path().join("sub"),
+        "predicate-order-preset = \"owl\"\nsubject-type-order = [\"ex:U\"]\n",
+    );
+    let skos = SpecialPredicateOrder::Skos.as_list().join(" ");
+    let owl = SpecialPredicateOrder::Owl.as_list().join(" ");
+
+    let mut resolver = resolver();
+    // A preset of an override replaces the list of the config, and vice versa
+    let options = resolver
+        .resolve(&dir.path().join("vocabularies/a.ttl"))
+        .unwrap();
+    assert_eq!(options.predicate_order().join(" "), skos);
+    assert_eq!(options.subject_type_order(), Some(vec!["ex:T".to_owned()]));
+
+    // The same for a closer config
+    let options = resolver.resolve(&dir.path().join("sub/a.ttl")).unwrap();
+    assert_eq!(options.predicate_order().join(" "), owl);
+    assert_eq!(options.subject_type_order(), Some(vec!["ex:U".to_owned()]));
+
+    // The same for explicit options
+    let explicit = PartialFormatOptions {
+        predicate_order_preset: Some(SpecialPredicateOrder::Skos),
+        subject_type_order: Some(vec!["ex:V".to_owned()]),
+        ..PartialFormatOptions::default()
+    };
+    let options = Resolver::new(FormatOptions::default(), explicit)
+        .resolve(&dir.path().join("a.ttl"))
+        .unwrap();
+    assert_eq!(options.predicate_order().join(" "), skos);
+    assert_eq!(options.subject_type_order(), Some(vec!["ex:V".to_owned()]));
+
+    let explicit = PartialFormatOptions {
+        predicate_order: Some(vec!["ex:q".to_owned()]),
+        ..PartialFormatOptions::default()
+    };
+    let options = Resolver::new(FormatOptions::default(), explicit)
+        .resolve(&dir.path().join("sub/a.ttl"))
+        .unwrap();
+    assert_eq!(options.predicate_order(), ["ex:q"]);
+}
+
+#[test]
+fn parent_dir_components_are_resolved() {
+    let dir = tempfile::tempdir().unwrap();
+    write_config(
+        dir.path(),
+        r#"root = true
+
+[[overrides]]
+files = ["vocabularies/*.ttl"]
+indentation = 4
+"#,
+    );
+    write_config(&dir.path().join("other"), "indentation = 3\n");
+    fs::create_dir_all(dir.path().join("vocabularies")).unwrap();
+
+    let options = resolver()
+        .resolve(&dir.path().join("other/../vocabularies/a.ttl"))
+        .unwrap();
+    assert_eq!(options.indentation, "    ");
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    write_config(
+        dir.path(),
+        r#"root = true
+indentaton = 4
+
+[[overrides]]
+files = ["*.ttl"]
+force = true
+"#,
+    );
+
+    match resolver().resolve(&dir.path().join("a.ttl")) {
+        Err(config::Error::UnknownKeys(keys, file)) => {
+            assert_eq!(keys, vec!["indentaton", "overrides.force"]);
+            assert_eq!(file, dir.path().join(FILE_NAME));
+        }
+        Err(err) => panic!("Unexpected error: {err}"),
+        Ok(_) => panic!("Unknown keys were accepted"),
+    }
+}
+
+#[test]
+fn clearing_the_cache_picks_up_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    write_config(dir.path(), "root = true\nindentation = 4\n");
+    let file = dir.path().join("a.ttl");
+
+    let mut resolver = resolver();
+    assert_eq!(resolver.resolve(&file).unwrap().indentation, "    ");
+    write_config(dir.path(), "root = true\nindentation = 1\n");
+    assert_eq!(resolver.resolve(&file).unwrap().indentation, "    ");
+    resolver.clear_cache();
+    assert_eq!(resolver.resolve(&file).unwrap().indentation, " ");
+}
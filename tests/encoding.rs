@@ -0,0 +1,26 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use pretty_assertions::assert_eq;
+use prttl::encoding::{self, TextStyle};
+use prttl::options::EndOfLine;
+
+#[test]
+fn keeps_bom_and_crlf() {
+    let original = "\u{feff}<a> <b> \"x\r\ny\" .\r\n";
+    let style = TextStyle::detect(original);
+    assert_eq!(
+        style.apply("<a> <b> \"x\r\ny\" .\n", EndOfLine::Auto),
+        original
+    );
+    assert_eq!(
+        style.apply("<a> <b> <c> .\n", EndOfLine::Lf),
+        "\u{feff}<a> <b> <c> .\n"
+    );
+}
+
+#[test]
+fn reports_offset_of_invalid_utf8() {
+    assert_eq!(encoding::decode(b"<a> <b> \"\xff\" .".to_vec()), Err(9));
+}
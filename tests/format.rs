@@ -3,11 +3,16 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{fs, path::Path, rc::Rc};
+use std::{fs, path::Path, sync::Arc};
 
 #[cfg(test)]
 use pretty_assertions::assert_eq;
-use prttl::{error::Error, formatter::format, options::FormatOptions, parser};
+use prttl::{
+    error::Error,
+    formatter::{format, format_dataset},
+    options::{EndOfLine, FormatOptions},
+    parser::{self, InputFormat},
+};
 
 fn fmt_opts_strict(single_object_on_new_line: bool) -> FormatOptions {
     FormatOptions {
@@ -26,23 +31,34 @@ fn fmt_opts_strict(single_object_on_new_line: bool) -> FormatOptions {
         subject_type_order: None,
         predicate_order_preset: None,
         predicate_order: None,
+        end_of_line: EndOfLine::Auto,
     }
 }
 
-fn format_turtle(original: &str, options: FormatOptions) -> Result<String, Error> {
-    let options = Rc::new(options);
-    let input = parser::parse(original.as_bytes(), &options)?;
+fn format_turtle(
+    original: &str,
+    input_format: InputFormat,
+    options: FormatOptions,
+) -> Result<String, Error> {
+    let options = Arc::new(options);
+    if input_format == InputFormat::TriG {
+        let dataset = parser::parse_trig(original.as_bytes(), &options)?;
+        return format_dataset(&dataset, options);
+    }
+    let input = parser::parse_as(original.as_bytes(), input_format, &options)?;
     format(&input, options)
 }
 
+#[allow(clippy::print_stderr)]
 fn test_format(
+    input_format: InputFormat,
     input: &str,
     expected: &str,
     debug_file: &Path,
     expected_file: &Path,
     fmt_options: FormatOptions,
 ) -> Result<(), Error> {
-    let output = format_turtle(input, fmt_options)?;
+    let output = format_turtle(input, input_format, fmt_options)?;
     let debug_file_abs =
         std::path::absolute(format!("target/tests/{}", debug_file.display())).unwrap();
     std::fs::create_dir_all(debug_file_abs.parent().unwrap()).unwrap();
@@ -66,8 +82,9 @@ meld tests/{} target/tests/{} &",
 }
 
 macro_rules! test_auto {
-    ($input:literal, $expected:literal, $stable:literal, $strict:literal, $single_object_on_new_line:literal) => {
+    ($input_format:expr; $input:literal, $expected:literal, $stable:literal, $strict:literal, $single_object_on_new_line:literal) => {
         test_format(
+            $input_format,
             include_str!($input),
             include_str!($expected),
             Path::new(&format!(
@@ -95,8 +112,19 @@ macro_rules! test_auto {
             },
         )
     };
+    ($input_format:expr; $input:literal, $expected:literal, $strict:literal, $single_object_on_new_line:literal) => {
+        test_auto!(
+            $input_format;
+            $input,
+            $expected,
+            false,
+            $strict,
+            $single_object_on_new_line
+        )
+    };
     ($input:literal, $expected:literal, $strict:literal, $single_object_on_new_line:literal) => {
         test_auto!(
+            InputFormat::Turtle;
             $input,
             $expected,
             false,
@@ -105,7 +133,7 @@ macro_rules! test_auto {
         )
     };
     ($input:literal, $strict:literal, $single_object_on_new_line:literal) => {
-        test_auto!($input, $input, true, $strict, $single_object_on_new_line)
+        test_auto!(InputFormat::Turtle; $input, $input, true, $strict, $single_object_on_new_line)
     };
 }
 
@@ -220,3 +248,129 @@ fn test_all_prtr() -> Result<(), Error> {
 fn test_all_prtr_stable() -> Result<(), Error> {
     test_auto!("data/output/pretty_printing/all_prtr.ttl", true, false)
 }
+
+#[test]
+fn test_convert_ntriples() -> Result<(), Error> {
+    test_auto!(
+        InputFormat::NTriples;
+        "fixtures/input/convert/simple.nt",
+        "fixtures/output/convert/simple.ttl",
+        false,
+        false
+    )
+}
+
+#[test]
+fn test_convert_rdf_xml() -> Result<(), Error> {
+    test_auto!(
+        InputFormat::RdfXml;
+        "fixtures/input/convert/simple.rdf",
+        "fixtures/output/convert/simple_rdf_xml.ttl",
+        false,
+        false
+    )
+}
+
+#[test]
+fn test_convert_json_ld() -> Result<(), Error> {
+    test_auto!(
+        InputFormat::JsonLd;
+        "fixtures/input/convert/simple.jsonld",
+        "fixtures/output/convert/simple_json_ld.ttl",
+        false,
+        false
+    )
+}
+
+#[test]
+fn test_trig() -> Result<(), Error> {
+    test_auto!(
+        InputFormat::TriG;
+        "fixtures/input/trig/named_graphs.trig",
+        "fixtures/output/trig/named_graphs.trig",
+        false,
+        false
+    )
+}
+
+#[test]
+fn test_trig_stable() -> Result<(), Error> {
+    test_auto!(
+        InputFormat::TriG;
+        "fixtures/output/trig/named_graphs.trig",
+        "fixtures/output/trig/named_graphs.trig",
+        true,
+        false,
+        false
+    )
+}
+
+#[test]
+fn test_trig_sparql_syntax() -> Result<(), Error> {
+    let mut fmt_options = fmt_opts_strict(false);
+    fmt_options.sparql_syntax = true;
+    test_format(
+        InputFormat::TriG,
+        include_str!("fixtures/input/trig/named_graphs.trig"),
+        include_str!("fixtures/output/trig/named_graphs.sparql.trig"),
+        Path::new("fixtures/output/trig/named_graphs.sparql.trig.actual_output.trig"),
+        Path::new("fixtures/output/trig/named_graphs.sparql.trig"),
+        fmt_options,
+    )
+}
+
+#[test]
+fn test_trig_canonicalize() -> Result<(), Error> {
+    let mut fmt_options = fmt_opts_strict(false);
+    fmt_options.canonicalize = true;
+    test_format(
+        InputFormat::TriG,
+        include_str!("fixtures/input/trig/named_graphs.trig"),
+        include_str!("fixtures/output/trig/named_graphs.canonical.trig"),
+        Path::new("fixtures/output/trig/named_graphs.canonical.trig.actual_output.trig"),
+        Path::new("fixtures/output/trig/named_graphs.canonical.trig"),
+        fmt_options,
+    )
+}
+
+#[test]
+fn test_trig_comment() {
+    let trig = "<http://example.org/g> {\n  # a comment\n  <a:s> <a:p> \"#\" .\n}\n";
+    let mut fmt_options = fmt_opts_strict(false);
+    fmt_options.force = false;
+    let result = format_turtle(trig, InputFormat::TriG, fmt_options);
+    assert!(matches!(
+        result,
+        Err(Error::ParseError(parser::Error::Comment))
+    ));
+
+    let without_comment = trig.replace("  # a comment\n", "");
+    let mut fmt_options = fmt_opts_strict(false);
+    fmt_options.force = false;
+    assert!(format_turtle(&without_comment, InputFormat::TriG, fmt_options).is_ok());
+}
+
+#[test]
+fn test_multiple_sorting_ids() {
+    let turtle = r#"@prefix prtr: <http://w3id.org/oseg/ont/prtr#> .
+[ prtr:sortingId 1, 2 ; <a:p> "x" ] .
+[ prtr:sortingId 3 ; <a:p> "y" ] .
+"#;
+    let result = format_turtle(turtle, InputFormat::Turtle, fmt_opts_strict(false));
+    assert!(matches!(
+        result,
+        Err(Error::SortingIdGeneration(
+            prttl::bn_sorting_ids::Error::MultipleSortingIds(_)
+        ))
+    ));
+}
+
+#[test]
+fn test_skip_marker() {
+    assert!(prttl::is_skipped("# prttl: skip\n<a> <b> <c> .\n"));
+    assert!(prttl::is_skipped(
+        "\u{feff}#prttl: skip - generated from schema.json\r\n<a> <b> <c> .\r\n"
+    ));
+    assert!(!prttl::is_skipped("# prttl: skipping\n<a> <b> <c> .\n"));
+    assert!(!prttl::is_skipped("<a> <b> <c> .\n# prttl: skip\n"));
+}
@@ -0,0 +1,218 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::fmt::Write;
+use std::fs;
+
+use pretty_assertions::assert_eq;
+use prttl::config::{FILE_NAME, Resolver};
+use prttl::lsp::{Position, TextEdit, TextRange, serve, text_edits};
+use prttl::options::{FormatOptions, PartialFormatOptions};
+use serde_json::{Value, json};
+
+fn edit(start: (u32, u32), end: (u32, u32), new_text: &str) -> TextEdit {
+    TextEdit {
+        range: TextRange {
+            start: Position {
+                line: start.0,
+                character: start.1,
+            },
+            end: Position {
+                line: end.0,
+                character: end.1,
+            },
+        },
+        new_text: new_text.to_owned(),
+    }
+}
+
+/// Runs the language server on the `messages`,
+/// and returns the messages it sends.
+fn session(messages: &[Value]) -> Vec<Value> {
+    let contents: Vec<String> = messages.iter().map(Value::to_string).collect();
+    raw_session(&contents)
+}
+
+/// Like [`session`], but with the content of the messages given as they are.
+fn raw_session(contents: &[String]) -> Vec<Value> {
+    let mut input = String::new();
+    for content in contents {
+        write!(input, "Content-Length: {}\r\n\r\n{content}", content.len()).unwrap();
+    }
+    let mut output = Vec::new();
+    let mut resolver = Resolver::new(
+        FormatOptions {
+            check: false,
+            ..FormatOptions::default()
+        },
+        PartialFormatOptions::default(),
+    );
+    serve(&mut resolver, input.as_bytes(), &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let mut sent = Vec::new();
+    let mut rest = output.as_str();
+    while let Some((head, tail)) = rest.split_once("\r\n\r\n") {
+        let length: usize = head
+            .strip_prefix("Content-Length: ")
+            .unwrap()
+            .parse()
+            .unwrap();
+        let (content, tail) = tail.split_at(length);
+        sent.push(serde_json::from_str(content).unwrap());
+        rest = tail;
+    }
+    sent
+}
+
+fn did_open(uri: &str, text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {
+            "textDocument": { "uri": uri, "languageId": "turtle", "version": 1, "text": text },
+        },
+    })
+}
+
+fn formatting(uri: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "textDocument/formatting",
+        "params": {
+            "textDocument": { "uri": uri },
+            "options": { "tabSize": 2, "insertSpaces": true },
+        },
+    })
+}
+
+/// The diagnostics published when opening a document.
+fn diagnostics(uri: &str, text: &str) -> Value {
+    let sent = session(&[did_open(uri, text)]);
+    sent.first()
+        .and_then(|notification| notification.pointer("/params/diagnostics"))
+        .unwrap()
+        .clone()
+}
+
+#[test]
+fn edits_replace_changed_lines() {
+    assert_eq!(
+        text_edits("a\nb\nc\n", "a\nB\nc\n"),
+        [edit((1, 0), (2, 0), "B\n")]
+    );
+    assert_eq!(text_edits("a\nb\n", "a\nb\n"), []);
+}
+
+#[test]
+fn edits_keep_crlf() {
+    assert_eq!(
+        text_edits("a\r\nb\r\nc\r\n", "a\r\nB\r\nc\r\n"),
+        [edit((1, 0), (2, 0), "B\r\n")]
+    );
+}
+
+#[test]
+fn edits_add_a_missing_trailing_newline() {
+    assert_eq!(text_edits("a\nb", "a\nb\n"), [edit((1, 0), (1, 1), "b\n")]);
+}
+
+#[test]
+fn edit_positions_count_utf16() {
+    // U+1F600 takes two UTF-16 code units
+    assert_eq!(
+        text_edits("\u{1F600} a\n\u{1F600} b", "\u{1F600} a\n\u{1F600} c"),
+        [edit((1, 0), (1, 4), "\u{1F600} c")]
+    );
+}
+
+#[test]
+fn diagnostics_point_at_comments() {
+    let text = "PREFIX ex: <http://example.org/>\nex:s ex:p \"\u{1F600}\" . # note\n";
+    let diagnostics = diagnostics("untitled:a.ttl", text);
+    assert_eq!(
+        diagnostics.pointer("/0/code"),
+        Some(&Value::String("comment".to_owned()))
+    );
+    assert_eq!(
+        diagnostics.pointer("/0/range"),
+        Some(&json!({
+            "start": { "line": 1, "character": 17 },
+            "end": { "line": 1, "character": 23 },
+        }))
+    );
+}
+
+#[test]
+fn quoted_triples_are_no_iris() {
+    let text = "PREFIX ex: <http://example.org/>\n\
+<< ex:s ex:p \"a > b # c\" >> ex:q ex:o .\n";
+    assert_eq!(diagnostics("untitled:a.ttl", text), json!([]));
+    assert_eq!(diagnostics("untitled:a.trig", text), json!([]));
+}
+
+#[test]
+fn local_file_uris_are_resolved() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(FILE_NAME),
+        "root = true\nindentation = 4\nsparql-syntax = true\n",
+    )
+    .unwrap();
+    let path = dir.path().join("a.ttl");
+    let path = path.to_str().unwrap();
+    let text = "PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o ; ex:q ex:o .\n";
+
+    for uri in [format!("file://{path}"), format!("file://localhost{path}")] {
+        let sent = session(&[did_open(&uri, text), formatting(&uri)]);
+        let new_text = sent
+            .get(1)
+            .and_then(|response| response.pointer("/result/0/newText"))
+            .and_then(Value::as_str)
+            .unwrap();
+        assert!(new_text.contains("\n    ex:q ex:o ;"), "{uri}: {new_text}");
+    }
+}
+
+#[test]
+fn remote_file_uris_are_rejected() {
+    let text = "PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n";
+    let diagnostics = diagnostics("file://example.org/a.ttl", text);
+    assert_eq!(
+        diagnostics.pointer("/0/code"),
+        Some(&Value::String("invalid-lsp-message".to_owned()))
+    );
+}
+
+#[test]
+fn invalid_messages_are_answered() {
+    let uri = "untitled:a.ttl";
+    let text = "PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n";
+    let sent = raw_session(&[
+        "{ not json".to_owned(),
+        "42".to_owned(),
+        did_open(uri, text).to_string(),
+        formatting(uri).to_string(),
+    ]);
+    let errors: Vec<_> = sent
+        .iter()
+        .filter_map(|message| message.get("error"))
+        .collect();
+    assert_eq!(
+        errors.iter().map(|error| error.get("code")).collect::<Vec<_>>(),
+        [Some(&json!(-32700)), Some(&json!(-32600))]
+    );
+    assert!(
+        sent.iter()
+            .filter(|message| message.get("error").is_some())
+            .all(|message| message.get("id") == Some(&Value::Null))
+    );
+    // The session goes on
+    let response = sent.last().unwrap();
+    assert_eq!(response.get("id"), Some(&json!(1)));
+    assert!(response.get("result").is_some_and(Value::is_array));
+}
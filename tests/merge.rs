@@ -0,0 +1,127 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use pretty_assertions::assert_eq;
+use prttl::merge::{Merged, merge};
+use prttl::options::FormatOptions;
+
+macro_rules! merge_fixture {
+    ($case:literal) => {
+        merge(
+            include_str!(concat!("fixtures/merge/", $case, "/base.ttl")),
+            include_str!(concat!("fixtures/merge/", $case, "/ours.ttl")),
+            include_str!(concat!("fixtures/merge/", $case, "/theirs.ttl")),
+            &Arc::new(FormatOptions::default()),
+        )
+        .unwrap()
+    };
+}
+
+fn conflicts(merged: &Merged) -> Vec<String> {
+    merged.conflicts.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn clean() {
+    let merged = merge_fixture!("clean");
+    assert_eq!(
+        merged.content,
+        include_str!("fixtures/merge/clean/merged.ttl")
+    );
+    assert!(merged.conflicts.is_empty());
+}
+
+#[test]
+fn prefixes() {
+    let merged = merge_fixture!("prefixes");
+    assert_eq!(
+        merged.content,
+        include_str!("fixtures/merge/prefixes/merged.ttl")
+    );
+    assert!(merged.conflicts.is_empty());
+}
+
+#[test]
+fn blank_nodes() {
+    let merged = merge_fixture!("blank_nodes");
+    assert_eq!(
+        merged.content,
+        include_str!("fixtures/merge/blank_nodes/merged.ttl")
+    );
+    assert!(merged.conflicts.is_empty());
+}
+
+#[test]
+fn conflict() {
+    let merged = merge_fixture!("conflict");
+    assert_eq!(
+        merged.content,
+        include_str!("fixtures/merge/conflict/merged.ttl")
+    );
+    assert_eq!(
+        conflicts(&merged),
+        vec![
+            "<http://example.org/a> <http://example.org/size>
+  base:   \"1\"^^<http://www.w3.org/2001/XMLSchema#integer>
+  ours:   \"2\"^^<http://www.w3.org/2001/XMLSchema#integer>
+  theirs: \"3\"^^<http://www.w3.org/2001/XMLSchema#integer>"
+        ]
+    );
+}
+
+#[cfg(feature = "cli")]
+mod driver {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+    use prttl::config::Resolver;
+    use prttl::merge::run_merge_driver;
+    use prttl::options::{FormatOptions, PartialFormatOptions};
+
+    fn run(case: &str) -> (usize, String) {
+        let fixtures = format!("tests/fixtures/merge/{case}");
+        let dir = tempfile::tempdir().unwrap();
+        for version in ["base", "ours", "theirs"] {
+            fs::copy(
+                format!("{fixtures}/{version}.ttl"),
+                dir.path().join(format!("{version}.ttl")),
+            )
+            .unwrap();
+        }
+        let ours = dir.path().join("ours.ttl");
+        let conflicts = run_merge_driver(
+            &mut Resolver::new(FormatOptions::default(), PartialFormatOptions::default()),
+            &dir.path().join("base.ttl"),
+            &ours,
+            &dir.path().join("theirs.ttl"),
+            None,
+        )
+        .unwrap();
+        (conflicts.len(), fs::read_to_string(ours).unwrap())
+    }
+
+    #[test]
+    fn writes_clean_merge_to_ours() {
+        assert_eq!(
+            run("clean"),
+            (
+                0,
+                include_str!("fixtures/merge/clean/merged.ttl").to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn writes_conflict_markers_to_ours() {
+        assert_eq!(
+            run("conflict"),
+            (
+                1,
+                include_str!("fixtures/merge/conflict/merged.ttl").to_owned()
+            )
+        );
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use pretty_assertions::assert_eq;
+use prttl::error::Error;
+use prttl::options::FormatOptions;
+use prttl::report::{FileReport, Report, Status};
+
+const PATCH: &str = "\
+--- original
++++ modified
+@@ -1,2 +1,3 @@
+-<http://example.org/s> <http://example.org/p> <http://example.org/o>.
++<http://example.org/s>
++  <http://example.org/p> <http://example.org/o> ;
++  .
+ 
+";
+
+fn report() -> Report {
+    let syntax_error = prttl::format_str(
+        "<http://example.org/s> <http://example.org/p> .\n",
+        &Arc::new(FormatOptions::default()),
+    )
+    .unwrap_err();
+    Report {
+        files: vec![
+            FileReport {
+                path: PathBuf::from("formatted.ttl"),
+                status: Status::Unchanged,
+            },
+            FileReport {
+                path: PathBuf::from("sub dir/needs #formatting.ttl"),
+                status: Status::NeedsFormatting(PATCH.to_owned()),
+            },
+            FileReport {
+                path: PathBuf::from("broken.ttl"),
+                status: Status::Error(Error::File(
+                    PathBuf::from("broken.ttl"),
+                    Box::new(syntax_error),
+                )),
+            },
+            FileReport {
+                path: PathBuf::from("/abs/missing.ttl"),
+                status: Status::Error(Error::TargetFileDoesNotExist(PathBuf::from(
+                    "/abs/missing.ttl",
+                ))),
+            },
+            FileReport {
+                path: PathBuf::from("generated.ttl"),
+                status: Status::Skipped,
+            },
+        ],
+    }
+}
+
+/// Replaces the version of prttl, which depends on the build.
+fn normalize(output: Vec<u8>) -> String {
+    String::from_utf8(output)
+        .unwrap()
+        .replace(&format!("\"{}\"", prttl::VERSION), "\"<version>\"")
+}
+
+#[test]
+fn json() {
+    let mut output = Vec::new();
+    report().write_json(&mut output).unwrap();
+    assert_eq!(
+        normalize(output),
+        include_str!("fixtures/report/report.json")
+    );
+}
+
+#[test]
+fn sarif() {
+    let mut output = Vec::new();
+    report().write_sarif(&mut output).unwrap();
+    assert_eq!(
+        normalize(output),
+        include_str!("fixtures/report/report.sarif")
+    );
+}
+
+#[test]
+fn human() {
+    let mut output = Vec::new();
+    report().write_human(&mut output, false, false).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        format!(
+            "sub dir/needs #formatting.ttl\n{PATCH}\
+generated.ttl: skipped, as formatting is turned off for it\n"
+        )
+    );
+
+    let mut output = Vec::new();
+    report().write_human(&mut output, true, false).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "sub dir/needs #formatting.ttl\n"
+    );
+}
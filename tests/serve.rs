@@ -0,0 +1,189 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use pretty_assertions::assert_eq;
+use prttl::options::FormatOptions;
+use serde_json::{Value, json};
+
+const UNFORMATTED: &str = "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n";
+
+const FORMATTED: &str = "PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n";
+
+/// Serves in the background on a free port, returning its address.
+fn start(max_body_size: usize) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        prttl::serve::serve(
+            &listener,
+            &Arc::new(FormatOptions::embedded()),
+            max_body_size,
+        );
+    });
+    addr
+}
+
+/// Sends the raw `request` to `addr`,
+/// and returns the status code, the headers and the body of the response.
+fn send(addr: SocketAddr, request: &str) -> (u16, String, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, head.to_owned(), body.to_owned())
+}
+
+fn post(addr: SocketAddr, target: &str, content_type: &str, body: &str) -> (u16, String, String) {
+    send(
+        addr,
+        &format!(
+            "POST {target} HTTP/1.1\r\nHost: localhost\r\nContent-Type: {content_type}\r\n\
+Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        ),
+    )
+}
+
+fn error_kind(body: &str) -> String {
+    let answer: Value = serde_json::from_str(body).unwrap();
+    answer
+        .pointer("/error/kind")
+        .unwrap()
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test]
+fn health() {
+    let addr = start(1024);
+    let (status, _, body) = send(addr, "GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    assert_eq!(status, 200);
+    assert_eq!(
+        serde_json::from_str::<Value>(&body).unwrap(),
+        json!({ "status": "ok", "version": prttl::VERSION })
+    );
+}
+
+#[test]
+fn formats_turtle() {
+    let addr = start(1024);
+    let (status, head, body) = post(addr, "/format", "text/turtle", UNFORMATTED);
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: text/turtle"));
+    assert_eq!(body, FORMATTED);
+}
+
+#[test]
+fn formats_trig_as_json() {
+    let addr = start(1024);
+    let request = json!({
+        "text": "<http://example.org/g> { <http://example.org/s> <http://example.org/p> 1, 2 . }",
+        "format": "trig",
+        "options": { "indentation": 4 },
+    });
+    let (status, _, body) = post(
+        addr,
+        "/format?single-leafed-new-lines",
+        "application/json",
+        &request.to_string(),
+    );
+    assert_eq!(status, 200);
+    let answer: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(answer.get("changed"), Some(&Value::Bool(true)));
+    assert_eq!(answer.get("skipped"), Some(&Value::Bool(false)));
+    // Indented as in the JSON options, with new lines as in the query options
+    let text = answer.get("text").unwrap().as_str().unwrap();
+    assert!(text.contains(concat!(
+        "    <http://example.org/s>\n",
+        "        <http://example.org/p>\n",
+        "            1 ,\n",
+    )));
+}
+
+#[test]
+fn applies_list_options_from_the_query() {
+    let addr = start(1024);
+    let (status, _, body) = post(
+        addr,
+        "/format?predicate-order=ex:q,ex:p",
+        "text/turtle",
+        "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o ; ex:q ex:o .\n",
+    );
+    assert_eq!(status, 200);
+    assert_eq!(
+        body,
+        "PREFIX ex: <http://example.org/>\n\nex:s\n  ex:q ex:o ;\n  ex:p ex:o ;\n  .\n"
+    );
+}
+
+#[test]
+fn leaves_skipped_content() {
+    let addr = start(1024);
+    let text = format!("# prttl: skip\n{UNFORMATTED}");
+    let request = json!({ "text": text });
+    let (status, _, body) = post(addr, "/format", "application/json", &request.to_string());
+    assert_eq!(status, 200);
+    assert_eq!(
+        serde_json::from_str::<Value>(&body).unwrap(),
+        json!({ "text": text, "changed": false, "skipped": true })
+    );
+}
+
+#[test]
+fn rejects_unknown_options() {
+    let addr = start(1024);
+    let (status, _, body) = post(addr, "/format?colour=red", "text/turtle", UNFORMATTED);
+    assert_eq!(status, 400);
+    assert_eq!(error_kind(&body), "invalid-options");
+}
+
+#[test]
+fn rejects_invalid_turtle() {
+    let addr = start(1024);
+    let (status, _, body) = post(addr, "/format", "text/turtle", "<a:s> <a:p> .");
+    assert_eq!(status, 422);
+    assert_eq!(error_kind(&body), "turtle-syntax");
+}
+
+#[test]
+fn rejects_oversized_bodies() {
+    let addr = start(16);
+    let (status, _, body) = send(
+        addr,
+        "POST /format HTTP/1.1\r\nHost: localhost\r\nContent-Length: 17\r\n\r\n",
+    );
+    assert_eq!(status, 413);
+    assert_eq!(error_kind(&body), "content-too-large");
+}
+
+#[test]
+fn requires_a_length() {
+    let addr = start(1024);
+    let (status, _, body) = send(addr, "POST /format HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    assert_eq!(status, 411);
+    assert_eq!(error_kind(&body), "length-required");
+}
+
+#[test]
+fn answers_concurrent_requests() {
+    let addr = start(1024);
+    let clients: Vec<_> = (0..16)
+        .map(|_| thread::spawn(move || post(addr, "/format", "text/turtle", UNFORMATTED)))
+        .collect();
+    for client in clients {
+        let (status, _, body) = client.join().unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, FORMATTED);
+    }
+}
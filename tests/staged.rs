@@ -0,0 +1,107 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use pretty_assertions::assert_eq;
+
+const UNFORMATTED: &str = "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o ; ex:q ex:o .\n";
+
+const FORMATTED: &str =
+    "PREFIX ex: <http://example.org/>\n\nex:s\n  ex:p ex:o ;\n  ex:q ex:o ;\n  .\n";
+
+fn git(repo: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn init_repo() -> tempfile::TempDir {
+    let repo = tempfile::tempdir().unwrap();
+    git(repo.path(), &["init", "--quiet"]);
+    git(repo.path(), &["config", "user.name", "Test"]);
+    git(repo.path(), &["config", "user.email", "test@example.org"]);
+    git(repo.path(), &["config", "core.autocrlf", "false"]);
+    repo
+}
+
+fn run_staged(repo: &Path) {
+    cargo_bin_cmd!("prttl")
+        .arg("--staged")
+        .current_dir(repo)
+        .assert()
+        .success();
+}
+
+fn staged(repo: &Path, file: &str) -> String {
+    git(repo, &["show", &format!(":{file}")])
+}
+
+#[test]
+fn formats_staged_files() {
+    let repo = init_repo();
+    fs::write(repo.path().join("a.ttl"), UNFORMATTED).unwrap();
+    fs::write(repo.path().join("unstaged.ttl"), UNFORMATTED).unwrap();
+    git(repo.path(), &["add", "a.ttl"]);
+
+    run_staged(repo.path());
+
+    assert_eq!(staged(repo.path(), "a.ttl"), FORMATTED);
+    assert_eq!(
+        fs::read_to_string(repo.path().join("a.ttl")).unwrap(),
+        FORMATTED
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path().join("unstaged.ttl")).unwrap(),
+        UNFORMATTED
+    );
+}
+
+#[test]
+fn keeps_unstaged_changes() {
+    let repo = init_repo();
+    let file = repo.path().join("a.ttl");
+    fs::write(&file, UNFORMATTED).unwrap();
+    git(repo.path(), &["add", "a.ttl"]);
+    let changed = format!("{UNFORMATTED}ex:s2 ex:p ex:o .\n");
+    fs::write(&file, &changed).unwrap();
+
+    run_staged(repo.path());
+
+    assert_eq!(staged(repo.path(), "a.ttl"), FORMATTED);
+    assert_eq!(fs::read_to_string(&file).unwrap(), changed);
+}
+
+#[test]
+fn applies_filters_to_the_working_copy() {
+    let repo = init_repo();
+    git(repo.path(), &["config", "core.autocrlf", "true"]);
+    let file = repo.path().join("a.ttl");
+    // Staged with LF line endings, so different from the working copy byte-wise
+    fs::write(&file, UNFORMATTED.replace('\n', "\r\n")).unwrap();
+    git(repo.path(), &["add", "a.ttl"]);
+    assert_eq!(staged(repo.path(), "a.ttl"), UNFORMATTED);
+
+    run_staged(repo.path());
+
+    assert_eq!(staged(repo.path(), "a.ttl"), FORMATTED);
+    assert_eq!(
+        fs::read_to_string(&file).unwrap().replace("\r\n", "\n"),
+        FORMATTED
+    );
+}
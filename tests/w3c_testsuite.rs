@@ -3,15 +3,21 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+// The tests of this file are disabled for now, see the bottom
+#![allow(dead_code, clippy::result_large_err)]
+
 use oxrdf::graph::CanonicalizationAlgorithm;
 use oxrdf::vocab::rdf;
 use oxrdf::{Graph, NamedNodeRef, NamedOrBlankNodeRef, TermRef};
 use oxttl::TurtleParser;
 use prttl::{
-    error::Error as FmtError, formatter::format, options::FormatOptions, parser,
+    error::Error as FmtError,
+    formatter::format,
+    options::{EndOfLine, FormatOptions},
+    parser,
     parser::Error as ParsingError,
 };
-use std::rc::Rc;
+use std::sync::Arc;
 use std::sync::LazyLock;
 use std::{fs, str};
 use thiserror::Error;
@@ -70,7 +76,7 @@ pub enum Error {
 }
 
 fn format_turtle(original: &str, options: FormatOptions) -> Result<String, Error> {
-    let options = Rc::new(options);
+    let options = Arc::new(options);
     let input = parser::parse(original.as_bytes(), &options)
         .map_err(|parser_err| Error::Parsing(parser_err, original.to_owned()))?;
     format(&input, options).map_err(|fmt_err| Error::Formatting(fmt_err, original.to_owned()))
@@ -116,6 +122,7 @@ fn options() -> FormatOptions {
         subject_type_order: None,
         predicate_order_preset: None,
         predicate_order: None,
+        end_of_line: EndOfLine::Auto,
     }
 }
 
@@ -0,0 +1,130 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pretty_assertions::assert_eq;
+use prttl::options::RunOptions;
+use prttl::walk::{IGNORE_FILE_NAME, find_files};
+
+fn touch(dir: &Path, relative: &str) {
+    let file = dir.join(relative);
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    fs::write(file, "").unwrap();
+}
+
+fn find_relative(dir: &Path, run_options: &RunOptions) -> Vec<PathBuf> {
+    find_files(dir, &["ttl"], run_options)
+        .unwrap()
+        .into_iter()
+        .map(|file| file.strip_prefix(dir).unwrap().to_path_buf())
+        .collect()
+}
+
+fn paths(relative: &[&str]) -> Vec<PathBuf> {
+    relative.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn sorted_and_filtered_by_extension() {
+    let dir = tempfile::tempdir().unwrap();
+    touch(dir.path(), "b.ttl");
+    touch(dir.path(), "a/c.ttl");
+    touch(dir.path(), "a.nt");
+    touch(dir.path(), ".hidden/d.ttl");
+
+    assert_eq!(
+        find_relative(dir.path(), &RunOptions::default()),
+        paths(&["a/c.ttl", "b.ttl"])
+    );
+}
+
+#[test]
+fn respects_ignore_files() {
+    let dir = tempfile::tempdir().unwrap();
+    touch(dir.path(), "keep.ttl");
+    touch(dir.path(), "generated/out.ttl");
+    touch(dir.path(), "vendor/upstream.ttl");
+    fs::write(dir.path().join(IGNORE_FILE_NAME), "generated/\n").unwrap();
+    fs::write(dir.path().join(".gitignore"), "vendor/\n").unwrap();
+
+    assert_eq!(
+        find_relative(dir.path(), &RunOptions::default()),
+        paths(&["keep.ttl"])
+    );
+}
+
+#[test]
+fn include_and_exclude() {
+    let dir = tempfile::tempdir().unwrap();
+    touch(dir.path(), "ontologies/a.ttl");
+    touch(dir.path(), "ontologies/draft/b.ttl");
+    touch(dir.path(), "other/c.ttl");
+
+    let run_options = RunOptions {
+        include: vec!["ontologies/**".to_owned()],
+        exclude: vec!["draft/".to_owned()],
+        ..RunOptions::default()
+    };
+    assert_eq!(
+        find_relative(dir.path(), &run_options),
+        paths(&["ontologies/a.ttl"])
+    );
+}
+
+#[test]
+fn include_does_not_bypass_ignore_files() {
+    let dir = tempfile::tempdir().unwrap();
+    touch(dir.path(), "ontologies/a.ttl");
+    touch(dir.path(), "ontologies/generated.ttl");
+    touch(dir.path(), ".hidden/b.ttl");
+    fs::write(dir.path().join(IGNORE_FILE_NAME), "generated.ttl\n").unwrap();
+
+    let run_options = RunOptions {
+        include: vec!["**/*.ttl".to_owned()],
+        ..RunOptions::default()
+    };
+    assert_eq!(
+        find_relative(dir.path(), &run_options),
+        paths(&["ontologies/a.ttl"])
+    );
+}
+
+#[test]
+fn invalid_glob() {
+    let dir = tempfile::tempdir().unwrap();
+    let run_options = RunOptions {
+        exclude: vec!["a/{b".to_owned()],
+        ..RunOptions::default()
+    };
+    let err = find_files(dir.path(), &["ttl"], &run_options).unwrap_err();
+    assert_eq!(err.kind(), "invalid-glob");
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks() {
+    let dir = tempfile::tempdir().unwrap();
+    touch(dir.path(), "real/a.ttl");
+    std::os::unix::fs::symlink(dir.path().join("real"), dir.path().join("link")).unwrap();
+    // A loop back to the root
+    std::os::unix::fs::symlink(dir.path(), dir.path().join("real/loop")).unwrap();
+
+    assert_eq!(
+        find_relative(dir.path(), &RunOptions::default()),
+        paths(&["real/a.ttl"])
+    );
+
+    let run_options = RunOptions {
+        follow_symlinks: true,
+        ..RunOptions::default()
+    };
+    assert_eq!(
+        find_relative(dir.path(), &run_options),
+        paths(&["link/a.ttl", "real/a.ttl"])
+    );
+}
@@ -0,0 +1,149 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use prttl::config::{FILE_NAME, Resolver};
+use prttl::options::{FormatOptions, PartialFormatOptions, RunOptions};
+
+const UNFORMATTED: &str = "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o ; ex:q ex:o .\n";
+
+/// How long to wait for a report that is expected.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often to retry a change that should be reported,
+/// as the watching only starts after the initial run was reported.
+const ATTEMPTS: usize = 10;
+
+/// The names of the files in a report, with their status.
+type Statuses = Vec<(String, &'static str)>;
+
+/// Watches `dir` in the background, sending the statuses of each report.
+fn watch(dir: &Path) -> Receiver<Statuses> {
+    let (sender, receiver) = mpsc::channel();
+    let src = vec![dir.to_owned()];
+    thread::spawn(move || {
+        let mut resolver = Resolver::new(
+            FormatOptions {
+                check: false,
+                ..FormatOptions::default()
+            },
+            PartialFormatOptions::default(),
+        );
+        prttl::watch::watch(
+            &mut resolver,
+            &RunOptions::default(),
+            &src,
+            &["ttl"],
+            |report| {
+                let statuses = report
+                    .files
+                    .iter()
+                    .map(|file| {
+                        let name = file.path.file_name().unwrap().to_string_lossy();
+                        (name.into_owned(), file.status.name())
+                    })
+                    .collect();
+                // The test may be over already
+                let _ = sender.send(statuses);
+            },
+        )
+        .unwrap();
+    });
+    receiver
+}
+
+/// Writes `content` to `path` until a report arrives,
+/// and returns that report.
+fn change(path: &PathBuf, content: &str, reports: &Receiver<Statuses>) -> Statuses {
+    for _ in 0..ATTEMPTS {
+        fs::write(path, content).unwrap();
+        match reports.recv_timeout(TIMEOUT) {
+            Ok(statuses) => return statuses,
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => panic!("Watching stopped"),
+        }
+    }
+    panic!("The change of '{}' was not reported", path.display());
+}
+
+#[test]
+fn formats_changed_files_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let changed = dir.path().join("changed.ttl");
+    let other = dir.path().join("other.ttl");
+    fs::write(&changed, UNFORMATTED).unwrap();
+    fs::write(&other, UNFORMATTED).unwrap();
+
+    let reports = watch(dir.path());
+    let mut initial = reports.recv_timeout(TIMEOUT).unwrap();
+    initial.sort_unstable();
+    assert_eq!(
+        initial,
+        [
+            ("changed.ttl".to_owned(), "formatted"),
+            ("other.ttl".to_owned(), "formatted"),
+        ]
+    );
+    let formatted = fs::read_to_string(&changed).unwrap();
+
+    assert_eq!(
+        change(&changed, UNFORMATTED, &reports),
+        [("changed.ttl".to_owned(), "formatted")]
+    );
+    assert_eq!(fs::read_to_string(&changed).unwrap(), formatted);
+    // Writing the formatted content is not reported as a change
+    assert_eq!(
+        reports.recv_timeout(TIMEOUT),
+        Err(RecvTimeoutError::Timeout)
+    );
+}
+
+#[test]
+fn formats_all_files_when_the_config_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("a.ttl");
+    fs::write(&file, UNFORMATTED).unwrap();
+
+    let reports = watch(dir.path());
+    reports.recv_timeout(TIMEOUT).unwrap();
+    assert!(fs::read_to_string(&file).unwrap().contains("\n  ex:q"));
+
+    assert_eq!(
+        change(
+            &dir.path().join(FILE_NAME),
+            "root = true\nindentation = 4\n",
+            &reports
+        ),
+        [("a.ttl".to_owned(), "formatted")]
+    );
+    assert!(fs::read_to_string(&file).unwrap().contains("\n    ex:q"));
+}
+
+#[test]
+fn formats_all_files_when_a_parent_config_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join(FILE_NAME);
+    let sub = dir.path().join("sub");
+    let file = sub.join("a.ttl");
+    fs::write(&config, "root = true\n").unwrap();
+    fs::create_dir(&sub).unwrap();
+    fs::write(&file, UNFORMATTED).unwrap();
+
+    let reports = watch(&sub);
+    reports.recv_timeout(TIMEOUT).unwrap();
+    assert!(fs::read_to_string(&file).unwrap().contains("\n  ex:q"));
+
+    assert_eq!(
+        change(&config, "root = true\nindentation = 4\n", &reports),
+        [("a.ttl".to_owned(), "formatted")]
+    );
+    assert!(fs::read_to_string(&file).unwrap().contains("\n    ex:q"));
+}
@@ -0,0 +1,243 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! WebAssembly bindings for [`prttl`],
+//! built with [wasm-pack](https://rustwasm.github.io/wasm-pack/) into a JavaScript package,
+//! for formatting Turtle in the browser, in web extensions or in Node.js.
+
+use std::sync::Arc;
+
+use prttl::error::Error;
+use prttl::options::{EndOfLine, FormatOptions, SpecialPredicateOrder, SpecialSubjectTypeOrder};
+use prttl::parser::InputFormat;
+use serde::{Deserialize, Serialize};
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT_TYPES: &str = r#"
+export type Preset = "owl" | "skos" | "shacl" | "shex" | "rdf";
+
+/**
+ * The formatting options, mirroring `FormatOptions` of the Rust library,
+ * with the defaults of the CLI.
+ */
+export interface FormatOptions {
+  /** Space(s) or tab(s) representing one level of indentation; two spaces by default. */
+  indentation?: string;
+  singleLeafedNewLines?: boolean;
+  /** Needed to format input with comments, which get removed. */
+  force?: boolean;
+  generateSortingIds?: boolean;
+  prioritizeInputOrder?: boolean;
+  prtrSorting?: boolean;
+  sparqlSyntax?: boolean;
+  maxNesting?: boolean;
+  canonicalize?: boolean;
+  subjectTypeOrderPreset?: Preset | null;
+  /** IRIs or prefixed names. */
+  subjectTypeOrder?: string[] | null;
+  predicateOrderPreset?: Preset | null;
+  /** IRIs or prefixed names. */
+  predicateOrder?: string[] | null;
+  endOfLine?: "auto" | "lf" | "crlf";
+}
+
+export interface FormatError {
+  /** A short, stable, machine-readable name of the error, like "comment". */
+  kind: string;
+  message: string;
+  /** Where in the input the error occurred, if known; 1-based. */
+  location?: {
+    startLine: number;
+    startColumn: number;
+    endLine: number;
+    endColumn: number;
+  };
+}
+
+export type FormatResult = { text: string } | { error: FormatError };
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "FormatOptions")]
+    pub type JsFormatOptions;
+
+    #[wasm_bindgen(typescript_type = "FormatResult")]
+    pub type JsFormatResult;
+}
+
+/// The options given from JavaScript.
+/// See the corresponding fields of [`FormatOptions`] for documentation.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+struct Options {
+    indentation: String,
+    single_leafed_new_lines: bool,
+    force: bool,
+    generate_sorting_ids: bool,
+    prioritize_input_order: bool,
+    prtr_sorting: bool,
+    sparql_syntax: bool,
+    max_nesting: bool,
+    canonicalize: bool,
+    subject_type_order_preset: Option<SpecialSubjectTypeOrder>,
+    subject_type_order: Option<Vec<String>>,
+    predicate_order_preset: Option<SpecialPredicateOrder>,
+    predicate_order: Option<Vec<String>>,
+    end_of_line: EndOfLine,
+}
+
+/// The defaults of the CLI,
+/// see [`FormatOptions::embedded`].
+impl Default for Options {
+    fn default() -> Self {
+        let defaults = FormatOptions::embedded();
+        Self {
+            indentation: defaults.indentation,
+            single_leafed_new_lines: defaults.single_leafed_new_lines,
+            force: defaults.force,
+            generate_sorting_ids: defaults.generate_sorting_ids,
+            prioritize_input_order: defaults.prioritize_input_order,
+            prtr_sorting: defaults.prtr_sorting,
+            sparql_syntax: defaults.sparql_syntax,
+            max_nesting: defaults.max_nesting,
+            canonicalize: defaults.canonicalize,
+            subject_type_order_preset: defaults.subject_type_order_preset,
+            subject_type_order: defaults.subject_type_order,
+            predicate_order_preset: defaults.predicate_order_preset,
+            predicate_order: defaults.predicate_order,
+            end_of_line: defaults.end_of_line,
+        }
+    }
+}
+
+impl From<Options> for FormatOptions {
+    fn from(options: Options) -> Self {
+        Self {
+            indentation: options.indentation,
+            single_leafed_new_lines: options.single_leafed_new_lines,
+            force: options.force,
+            generate_sorting_ids: options.generate_sorting_ids,
+            prioritize_input_order: options.prioritize_input_order,
+            prtr_sorting: options.prtr_sorting,
+            sparql_syntax: options.sparql_syntax,
+            max_nesting: options.max_nesting,
+            canonicalize: options.canonicalize,
+            subject_type_order_preset: options.subject_type_order_preset,
+            subject_type_order: options.subject_type_order,
+            predicate_order_preset: options.predicate_order_preset,
+            predicate_order: options.predicate_order,
+            end_of_line: options.end_of_line,
+            ..Self::embedded()
+        }
+    }
+}
+
+/// A range within the input.
+/// Lines and columns are 1-based.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    start_line: u64,
+    start_column: u64,
+    end_line: u64,
+    end_column: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FormatError {
+    kind: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
+}
+
+impl From<&Error> for FormatError {
+    fn from(err: &Error) -> Self {
+        Self {
+            kind: err.kind(),
+            message: err.to_string(),
+            location: err.location_1based().map(|range| Location {
+                start_line: range.start.line,
+                start_column: range.start.column,
+                end_line: range.end.line,
+                end_column: range.end.column,
+            }),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum FormatResult {
+    Text(String),
+    Error(FormatError),
+}
+
+fn parse_options(options: Option<JsFormatOptions>) -> Result<FormatOptions, FormatError> {
+    let options: Options = match options {
+        Some(options) => {
+            serde_wasm_bindgen::from_value(options.into()).map_err(|err| FormatError {
+                kind: "invalid-options",
+                message: format!("Invalid options: {err}"),
+                location: None,
+            })?
+        }
+        None => Options::default(),
+    };
+    Ok(options.into())
+}
+
+fn format(
+    text: &str,
+    input_format: InputFormat,
+    options: Option<JsFormatOptions>,
+) -> JsFormatResult {
+    let result = parse_options(options).and_then(|options| {
+        if prttl::is_skipped(text) {
+            return Ok(text.to_owned());
+        }
+        prttl::format_str_styled(text, input_format, &Arc::new(options))
+            .map_err(|err| FormatError::from(&err))
+    });
+    let result = match result {
+        Ok(formatted) => FormatResult::Text(formatted),
+        Err(err) => FormatResult::Error(err),
+    };
+    result
+        .serialize(&Serializer::json_compatible())
+        .unwrap_or_else(JsValue::from)
+        .unchecked_into()
+}
+
+/// Formats Turtle text.
+///
+/// Returns `{ text }` with the formatted text
+/// - or the text as it is, if it starts with a `# prttl: skip` comment -
+/// or `{ error }` if the text can not be formatted,
+/// like when it is not valid Turtle,
+/// or contains comments while `force` is not set.
+#[wasm_bindgen(js_name = formatTurtle)]
+#[must_use]
+pub fn format_turtle(text: &str, options: Option<JsFormatOptions>) -> JsFormatResult {
+    format(text, InputFormat::Turtle, options)
+}
+
+/// Formats TriG text,
+/// the same way as [`format_turtle`] does Turtle.
+#[wasm_bindgen(js_name = formatTrig)]
+#[must_use]
+pub fn format_trig(text: &str, options: Option<JsFormatOptions>) -> JsFormatResult {
+    format(text, InputFormat::TriG, options)
+}
+
+/// The version of prttl.
+#[wasm_bindgen]
+#[must_use]
+pub fn version() -> String {
+    prttl::VERSION.to_string()
+}
@@ -0,0 +1,95 @@
+// SPDX-FileCopyrightText: 2025 Robin Vobruba <hoijui.quaero@gmail.com>
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Run with `wasm-pack test --node wasm`.
+
+#![cfg(target_arch = "wasm32")]
+
+use prttl_wasm::{JsFormatResult, format_trig, format_turtle};
+use serde::Deserialize;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    start_line: u64,
+    start_column: u64,
+}
+
+#[derive(Deserialize)]
+struct FormatError {
+    kind: String,
+    location: Option<Location>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum FormatResult {
+    Text(String),
+    Error(FormatError),
+}
+
+fn result(result: JsFormatResult) -> FormatResult {
+    serde_wasm_bindgen::from_value(result.into()).unwrap()
+}
+
+fn options(json: &str) -> JsValue {
+    js_sys::JSON::parse(json).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn formats_turtle() {
+    let formatted = result(format_turtle(
+        "@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n",
+        None,
+    ));
+    assert!(matches!(
+        formatted,
+        FormatResult::Text(text) if text == "PREFIX ex: <http://example.org/>\n\nex:s ex:p ex:o .\n"
+    ));
+}
+
+#[wasm_bindgen_test]
+fn applies_options() {
+    let formatted = result(format_trig(
+        "<http://example.org/g> { <http://example.org/s> <http://example.org/p> 1, 2 . }",
+        Some(
+            options(r#"{ "indentation": "    ", "singleLeafedNewLines": true }"#).unchecked_into(),
+        ),
+    ));
+    assert!(matches!(formatted, FormatResult::Text(text) if text.contains("\n    ")));
+}
+
+#[wasm_bindgen_test]
+fn reports_errors_with_1based_locations() {
+    let failed = result(format_turtle(
+        "<http://example.org/s> <http://example.org/p> .",
+        None,
+    ));
+    let FormatResult::Error(err) = failed else {
+        panic!("Invalid Turtle was formatted");
+    };
+    assert_eq!(err.kind, "turtle-syntax");
+    let location = err.location.unwrap();
+    assert_eq!((location.start_line, location.start_column), (1, 47));
+}
+
+#[wasm_bindgen_test]
+fn leaves_skipped_text() {
+    let text = "# prttl: skip\n@prefix ex: <http://example.org/> .\nex:s ex:p ex:o.\n";
+    assert!(matches!(
+        result(format_turtle(text, None)),
+        FormatResult::Text(skipped) if skipped == text
+    ));
+}
+
+#[wasm_bindgen_test]
+fn rejects_invalid_options() {
+    let failed = result(format_turtle(
+        "",
+        Some(options(r#"{ "indentation": 4 }"#).unchecked_into()),
+    ));
+    assert!(matches!(failed, FormatResult::Error(err) if err.kind == "invalid-options"));
+}